@@ -0,0 +1,134 @@
+use crate::solution::Part;
+use anyhow::{bail, Context, Result};
+
+/// Accepted answers for a day's real input, stored as `answers/dayN.toml`:
+///
+/// ```toml
+/// part_1 = "67633"
+/// part_2 = "202899"
+/// ```
+///
+/// Answers spanning several lines use a `"""` string. A part without a key has
+/// no known answer yet.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\\"", "\"").replace("\\\\", "\\")
+}
+
+impl Answers {
+    /// `answers/dayN.toml`, or `answers/<profile>/dayN.toml` for the inputs
+    /// of a profile.
+    pub fn path(profile: Option<&str>, day: u8) -> String {
+        match profile {
+            Some(profile) => format!("answers/{}/day{}.toml", profile, day),
+            None => format!("answers/day{}.toml", day),
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part_1.as_deref(),
+            Part::Two => self.part_2.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, part: Part, answer: String) {
+        match part {
+            Part::One => self.part_1 = Some(answer),
+            Part::Two => self.part_2 = Some(answer),
+        }
+    }
+
+    /// Reads the subset of TOML written by [`Answers::to_toml`].
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let mut answers = Self::default();
+        let mut lines = toml.lines().enumerate();
+        while let Some((i, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let context = || format!("Invalid answer on line {}: {:?}", i + 1, line);
+            let (key, value) = line.split_once('=').with_context(context)?;
+            let value = value.trim();
+            let value = if value == "\"\"\"" {
+                let mut rows = vec![];
+                loop {
+                    let (_, row) = lines
+                        .next()
+                        .with_context(|| format!("Unclosed \"\"\" string from line {}", i + 1))?;
+                    match row.strip_suffix("\"\"\"") {
+                        Some(row) => {
+                            rows.push(row);
+                            break;
+                        }
+                        None => rows.push(row),
+                    }
+                }
+                unescape(&rows.join("\n"))
+            } else {
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .with_context(context)?;
+                unescape(value)
+            };
+            match key.trim() {
+                "part_1" => answers.part_1 = Some(value),
+                "part_2" => answers.part_2 = Some(value),
+                key => bail!("Unknown key {} on line {}", key, i + 1),
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn to_toml(&self) -> String {
+        [("part_1", &self.part_1), ("part_2", &self.part_2)]
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let value = value.as_ref()?;
+                Some(if value.contains('\n') {
+                    format!("{} = \"\"\"\n{}\"\"\"\n", key, escape(value))
+                } else {
+                    format!("{} = \"{}\"\n", key, escape(value))
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let answers = Answers {
+            part_1: Some("17020".to_string()),
+            part_2: Some("\n#..\"\n.#\\".to_string()),
+        };
+        let toml = answers.to_toml();
+        assert_eq!(
+            toml,
+            "part_1 = \"17020\"\npart_2 = \"\"\"\n\n#..\\\"\n.#\\\\\"\"\"\n"
+        );
+        assert_eq!(Answers::from_toml(&toml).unwrap(), answers);
+    }
+
+    #[test]
+    fn test_missing_part() {
+        let answers = Answers::from_toml("# no part 2 yet\npart_1 = \"1991\"\n").unwrap();
+        assert_eq!(answers.get(Part::One), Some("1991"));
+        assert_eq!(answers.get(Part::Two), None);
+        assert!(Answers::from_toml("part_3 = \"1\"").is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::{Context, Result};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct BenchOptions {
+    pub warmup: usize,
+    pub runs: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            warmup: 2,
+            runs: 10,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Summarises a set of timings, `None` if there are none.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+        let p95 = (samples.len() * 95).div_ceil(100) - 1;
+        Some(Self {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            p95: samples[p95],
+        })
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:>12?}  median {:>12?}  p95 {:>12?}",
+            self.min, self.median, self.p95
+        )
+    }
+}
+
+/// Times `f` `options.runs` times after `options.warmup` untimed calls.
+pub fn measure<T, F: FnMut() -> T>(options: &BenchOptions, mut f: F) -> Stats {
+    for _ in 0..options.warmup {
+        std::hint::black_box(f());
+    }
+    let samples = (0..std::cmp::max(options.runs, 1))
+        .map(|_| {
+            let before = Instant::now();
+            std::hint::black_box(f());
+            before.elapsed()
+        })
+        .collect();
+    Stats::from_samples(samples).unwrap()
+}
+
+/// Benchmark results keyed by day and step (e.g. `(1, "part 2")`), in day
+/// order, saved as JSON so a later run can be compared against it.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+    pub results: BTreeMap<(u8, String), Stats>,
+}
+
+impl Baseline {
+    /// One entry per line so [`Baseline::from_json`] can read it back without
+    /// a full JSON parser.
+    pub fn to_json(&self) -> String {
+        let entries = self
+            .results
+            .iter()
+            .map(|((day, step), stats)| {
+                format!(
+                    "  {:?}: {{\"min_ns\": {}, \"median_ns\": {}, \"p95_ns\": {}}}",
+                    format!("day {} {}", day, step),
+                    stats.min.as_nanos(),
+                    stats.median.as_nanos(),
+                    stats.p95.as_nanos()
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("{{\n{}\n}}\n", entries.join(",\n"))
+    }
+
+    /// Reads a baseline written by [`Baseline::to_json`].
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut results = BTreeMap::new();
+        for (i, line) in json.lines().enumerate() {
+            let line = line.trim().trim_end_matches(',');
+            if line == "{" || line == "}" || line.is_empty() {
+                continue;
+            }
+            let context = || format!("Invalid baseline entry on line {}: {:?}", i + 1, line);
+            let (name, fields) = line.split_once("\": {").with_context(context)?;
+            let (day, step) = name
+                .strip_prefix("\"day ")
+                .and_then(|name| name.split_once(' '))
+                .with_context(context)?;
+            let day = day.parse::<u8>().with_context(context)?;
+            let field = |key: &str| -> Result<Duration> {
+                let (_, rest) = fields
+                    .split_once(&format!("\"{}\": ", key))
+                    .with_context(context)?;
+                let end = rest.find([',', '}']).unwrap_or(rest.len());
+                let nanos = rest[..end].trim().parse::<u64>().with_context(context)?;
+                Ok(Duration::from_nanos(nanos))
+            };
+            let stats = Stats {
+                min: field("min_ns")?,
+                median: field("median_ns")?,
+                p95: field("p95_ns")?,
+            };
+            results.insert((day, step.replace("\\\"", "\"")), stats);
+        }
+        Ok(Self { results })
+    }
+
+    /// Change in median time of every entry also found in `baseline`, as a
+    /// percentage (positive means slower).
+    pub fn compare(&self, baseline: &Baseline) -> Vec<((u8, String), f64)> {
+        self.results
+            .iter()
+            .filter_map(|(key, stats)| {
+                let old = baseline.results.get(key)?;
+                let old = old.median.as_secs_f64();
+                let change = if old == 0.0 {
+                    0.0
+                } else {
+                    (stats.median.as_secs_f64() - old) / old * 100.0
+                };
+                Some((key.clone(), change))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = Stats::from_samples(samples).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(11));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(Stats::from_samples(vec![]), None);
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let stats = |ms| Stats {
+            min: Duration::from_millis(ms),
+            median: Duration::from_millis(ms * 2),
+            p95: Duration::from_millis(ms * 3),
+        };
+        let key = |day, step: &str| (day, step.to_string());
+        let mut baseline = Baseline::default();
+        baseline.results.insert(key(1, "parse"), stats(1));
+        baseline.results.insert(key(1, "part 1"), stats(4));
+        baseline.results.insert(key(10, "part 1 (fast)"), stats(3));
+        let json = baseline.to_json();
+        assert!(json.contains("\"day 10 part 1 (fast)\": {\"min_ns\": 3000000,"));
+        let read = Baseline::from_json(&json).unwrap();
+        assert_eq!(read, baseline);
+
+        let mut current = Baseline::default();
+        current.results.insert(key(1, "part 1"), stats(2));
+        current.results.insert(key(2, "part 1"), stats(2));
+        assert_eq!(current.compare(&baseline), vec![(key(1, "part 1"), -50.0)]);
+    }
+
+    #[test]
+    fn test_day_order() {
+        let json = "{
+  \"day 10 part 1\": {\"min_ns\": 1, \"median_ns\": 1, \"p95_ns\": 1},
+  \"day 2 part 1\": {\"min_ns\": 1, \"median_ns\": 1, \"p95_ns\": 1},
+  \"day 16 parse\": {\"min_ns\": 1, \"median_ns\": 1, \"p95_ns\": 1}
+}";
+        let read = Baseline::from_json(json).unwrap();
+        let days = read.results.keys().map(|(day, _)| *day).collect::<Vec<_>>();
+        assert_eq!(days, vec![2, 10, 16]);
+        assert!(Baseline::from_json("{\n  \"part 1\": {\"min_ns\": 1}\n}").is_err());
+    }
+}
@@ -0,0 +1,366 @@
+use advent_of_code_2022::answers::Answers;
+use advent_of_code_2022::bench::{Baseline, BenchOptions};
+use advent_of_code_2022::days;
+use advent_of_code_2022::examples;
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::solution::{ImageFormat, Part, Runner};
+use advent_of_code_2022::utill::parse_error::ParseError;
+use anyhow::{bail, Context, Result};
+use std::fs::{create_dir_all, read_to_string, write};
+
+const USAGE: &str = "usage: aoc <day|all> [--part <1|2>] [--visualize] [--color] [--image <file.pgm|file.ppm>] [input]
+       aoc verify <day|all> [--record] [input]
+       aoc example <day> <puzzle.html|puzzle.md> [--block <n>]
+       aoc bench <day|all> [--runs <n>] [--warmup <n>] [--save <file>] [--baseline <file>] [input]
+input: [--input <file|->] [--profile <name>], inputs are read from $AOC_INPUT_DIR (default inputs)";
+
+/// Prints an error, showing the offending input line when it came from a
+/// parser.
+fn report(prefix: &str, e: &anyhow::Error) {
+    match e.chain().find_map(|c| c.downcast_ref::<ParseError>()) {
+        Some(parse_error) => eprintln!("{}: error: {}", prefix, parse_error.render()),
+        None => eprintln!("{}: error: {:#}", prefix, e),
+    }
+}
+
+/// How to draw a day after running it, `Some(color)` to draw it at all.
+type Visualize = Option<bool>;
+
+/// File to write a day's image to and the format it gets from its extension.
+type Image = Option<(String, ImageFormat)>;
+
+/// Runs the requested parts of a day and prints each answer, then its picture
+/// and image if asked to. Returns false if reading, parsing, any part or
+/// drawing failed.
+fn run_day(
+    runner: &dyn Runner,
+    source: &InputSource,
+    parts: &[Part],
+    visualize: Visualize,
+    image: &Image,
+) -> bool {
+    let results = source
+        .read(runner.day())
+        .and_then(|input| Ok((runner.run(&input, parts)?, input)));
+    let (results, input) = match results {
+        Ok(results) => results,
+        Err(e) => {
+            report(&format!("day {}", runner.day()), &e);
+            return false;
+        }
+    };
+    let mut ok = true;
+    for (part, answer) in results {
+        match answer {
+            Ok(answer) => println!("day {} part {}: {}", runner.day(), part, answer),
+            Err(e) => {
+                report(&format!("day {} part {}", runner.day(), part), &e);
+                ok = false;
+            }
+        }
+    }
+    if let Some(color) = visualize {
+        match runner.visualize(&input, color) {
+            Ok(Some(picture)) => println!("{}", picture),
+            Ok(None) => println!("day {}: nothing to visualize", runner.day()),
+            Err(e) => {
+                report(&format!("day {}", runner.day()), &e);
+                ok = false;
+            }
+        }
+    }
+    if let Some((path, format)) = image {
+        let written = runner.image(&input, *format).and_then(|image| match image {
+            Some(image) => {
+                write(path, image).context(format!("Error writing {}", path))?;
+                Ok(true)
+            }
+            None => Ok(false),
+        });
+        match written {
+            Ok(true) => println!("day {}: image written to {}", runner.day(), path),
+            Ok(false) => println!("day {}: no image to write", runner.day()),
+            Err(e) => {
+                report(&format!("day {}", runner.day()), &e);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+/// Checks a day's answers against its [`Answers`] file, recording the ones
+/// missing from it if `record` is set. Returns false on any mismatch or error.
+fn verify_day(runner: &dyn Runner, source: &InputSource, record: bool) -> bool {
+    let day = runner.day();
+    let results = source
+        .read(day)
+        .and_then(|input| runner.run(&input, &[Part::One, Part::Two]));
+    let answers_path = Answers::path(source.profile.as_deref(), day);
+    let answers = match read_to_string(&answers_path) {
+        Ok(toml) => Answers::from_toml(&toml).context(answers_path.clone()),
+        Err(_) => Ok(Answers::default()),
+    };
+    let (results, mut answers) = match results.and_then(|r| Ok((r, answers?))) {
+        Ok(ok) => ok,
+        Err(e) => {
+            report(&format!("day {}", day), &e);
+            return false;
+        }
+    };
+    let mut ok = true;
+    let mut recorded = false;
+    for (part, answer) in results {
+        let answer = match answer {
+            Ok(answer) => answer,
+            Err(e) => {
+                report(&format!("day {} part {}", day, part), &e);
+                ok = false;
+                continue;
+            }
+        };
+        match answers.get(part) {
+            Some(expected) if expected == answer => println!("day {} part {}: ok", day, part),
+            Some(expected) => {
+                eprintln!(
+                    "day {} part {}: mismatch, expected {} but got {}",
+                    day, part, expected, answer
+                );
+                ok = false;
+            }
+            None if record => {
+                println!("day {} part {}: recorded {}", day, part, answer);
+                answers.set(part, answer);
+                recorded = true;
+            }
+            None => println!("day {} part {}: no recorded answer", day, part),
+        }
+    }
+    if recorded {
+        let written = std::path::Path::new(&answers_path)
+            .parent()
+            .map_or(Ok(()), create_dir_all)
+            .and_then(|_| write(&answers_path, answers.to_toml()));
+        if let Err(e) = written {
+            report(
+                &format!("day {}", day),
+                &anyhow::Error::new(e).context(answers_path),
+            );
+            return false;
+        }
+    }
+    ok
+}
+
+fn verify(source: &InputSource, mut args: impl Iterator<Item = String>) -> Result<()> {
+    let runners = select(&args.next().context(USAGE)?, source)?;
+    let mut record = false;
+    for arg in args {
+        match arg.as_str() {
+            "--record" => record = true,
+            _ => bail!("Unknown argument {}\n{}", arg, USAGE),
+        }
+    }
+    let failed = runners
+        .iter()
+        .filter(|runner| !verify_day(runner.as_ref(), source, record))
+        .count();
+    if failed > 0 {
+        bail!("{} day(s) failed verification", failed);
+    }
+    Ok(())
+}
+
+/// Benchmarks a day, adding its timings to `results`. Returns false if
+/// reading the input or any step failed.
+fn bench_day(
+    runner: &dyn Runner,
+    source: &InputSource,
+    options: &BenchOptions,
+    results: &mut Baseline,
+) -> bool {
+    let stats = source
+        .read(runner.day())
+        .and_then(|input| runner.bench(&input, options));
+    match stats {
+        Ok(stats) => {
+            for (name, stats) in stats {
+                results.results.insert((runner.day(), name), stats);
+            }
+            true
+        }
+        Err(e) => {
+            report(&format!("day {}", runner.day()), &e);
+            false
+        }
+    }
+}
+
+/// Saves an example block of a puzzle description, with the answers found in
+/// it, as the next `examples/dayN/exampleK.txt`.
+fn example(mut args: impl Iterator<Item = String>) -> Result<()> {
+    let day = args.next().context(USAGE)?;
+    let day = day
+        .parse::<u8>()
+        .context(format!("Invalid day {}\n{}", day, USAGE))?;
+    let path = args.next().context(USAGE)?;
+    let mut block: usize = 1;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--block" => block = args.next().context(USAGE)?.parse().context(USAGE)?,
+            _ => bail!("Unknown argument {}\n{}", arg, USAGE),
+        }
+    }
+
+    let description = read_to_string(&path).context(format!("Error reading file {}", path))?;
+    let puzzle = examples::extract(&description);
+    let input = block
+        .checked_sub(1)
+        .and_then(|i| puzzle.blocks.get(i))
+        .context(format!(
+            "No code block {}, found {}",
+            block,
+            puzzle.blocks.len()
+        ))?;
+    let dir = examples::dir(day);
+    create_dir_all(&dir).context(format!("Error creating {}", dir))?;
+    let name = (1..)
+        .map(|k| format!("{}/example{}", dir, k))
+        .find(|name| !std::path::Path::new(&format!("{}.txt", name)).exists())
+        .unwrap();
+    write(format!("{}.txt", name), input).context(format!("Error writing {}.txt", name))?;
+    write(format!("{}.toml", name), puzzle.answers.to_toml())
+        .context(format!("Error writing {}.toml", name))?;
+    println!(
+        "{}.txt: part 1 {}, part 2 {}",
+        name,
+        puzzle.answers.part_1.as_deref().unwrap_or("not found"),
+        puzzle.answers.part_2.as_deref().unwrap_or("not found")
+    );
+    Ok(())
+}
+
+/// Takes the input options out of `args`, returning the source they describe
+/// and the remaining arguments.
+fn input_source(mut args: impl Iterator<Item = String>) -> Result<(InputSource, Vec<String>)> {
+    let mut source = InputSource::from_env();
+    let mut rest = vec![];
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => source.file = Some(args.next().context(USAGE)?),
+            "--profile" => source.profile = Some(args.next().context(USAGE)?),
+            _ => rest.push(arg),
+        }
+    }
+    Ok((source, rest))
+}
+
+/// Parses a `<day|all>` argument into the runners it selects.
+fn select(day: &str, source: &InputSource) -> Result<Vec<Box<dyn Runner>>> {
+    if day == "all" {
+        if source.file.is_some() {
+            bail!("--input can only be used with a single day\n{}", USAGE);
+        }
+        return Ok(days::registry());
+    }
+    let day = day
+        .parse::<u8>()
+        .context(format!("Invalid day {}\n{}", day, USAGE))?;
+    Ok(vec![
+        days::get(day).context(format!("Day {} isn't solved yet", day))?
+    ])
+}
+
+fn bench(source: &InputSource, mut args: impl Iterator<Item = String>) -> Result<()> {
+    let runners = select(&args.next().context(USAGE)?, source)?;
+    let mut options = BenchOptions::default();
+    let mut save = None;
+    let mut baseline = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().context(USAGE);
+        match arg.as_str() {
+            "--runs" => options.runs = value()?.parse().context(USAGE)?,
+            "--warmup" => options.warmup = value()?.parse().context(USAGE)?,
+            "--save" => save = Some(value()?),
+            "--baseline" => {
+                let path = value()?;
+                let json = read_to_string(&path).context(format!("Error reading {}", path))?;
+                baseline = Some(Baseline::from_json(&json).context(path)?);
+            }
+            _ => bail!("Unknown argument {}\n{}", arg, USAGE),
+        }
+    }
+
+    let mut results = Baseline::default();
+    let failed = runners
+        .iter()
+        .filter(|runner| !bench_day(runner.as_ref(), source, &options, &mut results))
+        .count();
+    let changes = baseline
+        .map(|baseline| results.compare(&baseline))
+        .unwrap_or_default();
+    for (key, stats) in results.results.iter() {
+        let name = format!("day {} {}", key.0, key.1);
+        match changes.iter().find(|(changed, _)| changed == key) {
+            Some((_, change)) => println!("{:<40} {}  {:+.1}%", name, stats, change),
+            None => println!("{:<40} {}", name, stats),
+        }
+    }
+    if let Some(path) = save {
+        write(&path, results.to_json()).context(format!("Error writing {}", path))?;
+    }
+    if failed > 0 {
+        bail!("{} day(s) failed", failed);
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let (source, args) = input_source(std::env::args().skip(1))?;
+    let mut args = args.into_iter();
+    let day = args.next().context(USAGE)?;
+    match day.as_str() {
+        "verify" => return verify(&source, args),
+        "bench" => return bench(&source, args),
+        "example" => return example(args),
+        _ => {}
+    }
+    let mut parts = vec![Part::One, Part::Two];
+    let mut visualize = None;
+    let mut image = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--visualize" => visualize = Some(visualize.unwrap_or(false)),
+            "--color" => visualize = Some(true),
+            "--image" => {
+                let path = args.next().context(USAGE)?;
+                let format = ImageFormat::from_path(&path).context(format!(
+                    "Image {} must end in .pgm or .ppm\n{}",
+                    path, USAGE
+                ))?;
+                image = Some((path, format));
+            }
+            "--part" => {
+                parts = match args.next().context(USAGE)?.as_str() {
+                    "1" => vec![Part::One],
+                    "2" => vec![Part::Two],
+                    p => bail!("Invalid part {}\n{}", p, USAGE),
+                }
+            }
+            _ => bail!("Unknown argument {}\n{}", arg, USAGE),
+        }
+    }
+
+    if image.is_some() && day == "all" {
+        bail!("--image can only be used with a single day\n{}", USAGE);
+    }
+    let runners = select(&day, &source)?;
+    let failed = runners
+        .iter()
+        .filter(|runner| !run_day(runner.as_ref(), &source, &parts, visualize, &image))
+        .count();
+    if failed > 0 {
+        bail!("{} day(s) failed", failed);
+    }
+    Ok(())
+}
@@ -1,11 +1,26 @@
+use crate::solution::{Part, Solution, Variant};
+use crate::utill::parse_error::ParseError;
+use anyhow::{Context, Result};
 // use crate::utill::parse_iter::ParseIterator;
 use std::collections::BinaryHeap;
-pub fn get_n_maxes<const N: usize>(input: &str) -> u32 {
+
+/// Total calories carried by each elf.
+fn elves(input: &str) -> Result<Vec<u32>, ParseError> {
+    input
+        .split("\n\n")
+        .map(|chunk| {
+            chunk
+                .lines()
+                .map(|line| ParseError::parse::<u32>(input, line, "a calorie count"))
+                .sum()
+        })
+        .collect()
+}
+
+pub fn get_n_maxes<const N: usize>(elves: &[u32]) -> u32 {
     // O(N + nN + N)
     let mut maxes = [0; N];
-    for chunk in input.split("\n\n") {
-        let current = chunk.lines().flat_map(str::parse::<u32>).sum::<u32>();
-        // let current = chunk.lines().parse::<u32>().flatten().sum::<u32>();
+    for &current in elves {
         for (i, &max) in maxes.iter().enumerate() {
             // if we found a new max replace it
             if current > max {
@@ -18,58 +33,92 @@ pub fn get_n_maxes<const N: usize>(input: &str) -> u32 {
     return maxes.into_iter().sum::<u32>();
 }
 
-pub fn get_3_maxes_heap(input: &str) -> u32 {
-    let mut heap: BinaryHeap<u32> = BinaryHeap::new();
-    for chunk in input.split("\n\n") {
-        heap.push(chunk.lines().flat_map(str::parse::<u32>).sum());
-    }
-    return heap.pop().unwrap() + heap.pop().unwrap() + heap.pop().unwrap();
+pub fn get_3_maxes_heap(elves: &[u32]) -> Result<u32> {
+    let mut heap = elves.iter().copied().collect::<BinaryHeap<u32>>();
+    let mut pop = || heap.pop().context("Need at least 3 elves");
+    return Ok(pop()? + pop()? + pop()?);
 }
 
-pub fn get_slow_n_maxes(input: &str, n: usize) -> u32 {
+pub fn get_slow_n_maxes(elves: &[u32], n: usize) -> Result<u32> {
     //O(nlogn + N) N = input n n = size of input
-    let mut v = input
-        .split("\n\n")
-        .map(|chunk| chunk.lines().flat_map(str::parse::<u32>).sum::<u32>())
-        .collect::<Vec<_>>();
+    let mut v = elves.to_vec();
     v.sort_by(|a, b| b.cmp(a));
-    return v[0..n].into_iter().sum::<u32>();
+    return Ok(v
+        .get(0..n)
+        .context(format!("Need at least {} elves", n))?
+        .iter()
+        .sum::<u32>());
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use std::time::Instant;
-    const INPUT: &'static str = "1000
-2000
-3000
+pub fn part_1(input: &str) -> Result<u32> {
+    Day1::part_1(&Day1::parse(input)?)
+}
 
-4000
+pub fn part_2(input: &str) -> Result<u32> {
+    Day1::part_2(&Day1::parse(input)?)
+}
 
-5000
-6000
+pub struct Day1;
 
-7000
-8000
-9000
+impl Solution for Day1 {
+    type Parsed = Vec<u32>;
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-10000";
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(elves(input)?)
+    }
+
+    fn part_1(elves: &Self::Parsed) -> Result<Self::Answer1> {
+        Ok(get_n_maxes::<1>(elves))
+    }
+
+    fn part_2(elves: &Self::Parsed) -> Result<Self::Answer2> {
+        Ok(get_n_maxes::<3>(elves))
+    }
+
+    fn variants() -> Vec<Variant<Self::Parsed>> {
+        vec![
+            Variant {
+                name: "get_slow_n_maxes",
+                part: Part::One,
+                run: |elves| Ok(get_slow_n_maxes(elves, 1)?.to_string()),
+            },
+            Variant {
+                name: "get_slow_n_maxes",
+                part: Part::Two,
+                run: |elves| Ok(get_slow_n_maxes(elves, 3)?.to_string()),
+            },
+            Variant {
+                name: "get_3_maxes_heap",
+                part: Part::Two,
+                run: |elves| Ok(get_3_maxes_heap(elves)?.to_string()),
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const INPUT: &str = include_str!("../../examples/day1/example1.txt");
 
     #[test]
     fn test_day1() {
-        assert_eq!(get_n_maxes::<1>(INPUT), 24000);
-        assert_eq!(get_n_maxes::<3>(INPUT), 45000);
-        assert_eq!(get_n_maxes::<1>(INPUT), get_slow_n_maxes(INPUT, 1));
-        assert_eq!(get_n_maxes::<3>(INPUT), get_slow_n_maxes(INPUT, 3));
+        assert_eq!(part_1(INPUT).unwrap(), 24000);
+        assert_eq!(part_2(INPUT).unwrap(), 45000);
+        let elves = Day1::parse(INPUT).unwrap();
+        assert_eq!(get_slow_n_maxes(&elves, 1).unwrap(), 24000);
+        assert_eq!(get_slow_n_maxes(&elves, 3).unwrap(), 45000);
+        assert_eq!(get_3_maxes_heap(&elves).unwrap(), 45000);
     }
 
     #[test]
-    fn bench() {
-        let before = Instant::now();
-        get_n_maxes::<3>(INPUT);
-        println!("fast: {:?}", before.elapsed());
-        let before = Instant::now();
-        get_slow_n_maxes(INPUT, 3);
-        println!("slow: {:?}", before.elapsed());
+    fn test_bad_input() {
+        let err = part_2("1000\n\n20x0").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, columns 1-4: expected a calorie count"
+        );
     }
 }
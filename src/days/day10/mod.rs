@@ -1,54 +1,66 @@
-pub fn part_1(input: &str) -> isize {
+use crate::solution::Solution;
+use crate::utill::parse_error::ParseError;
+use anyhow::Result;
+
+/// Value of the x register during every cycle, addx taking two cycles.
+fn register_values(input: &str) -> Result<Vec<isize>, ParseError> {
     let mut x: isize = 1;
-    let mut sum: isize = 0;
-    for (i, instruction) in input
-        .lines()
-        .flat_map(|instruction| {
-            if instruction.starts_with("addx") {
-                return vec!["noop", instruction];
-            } else {
-                return vec![instruction];
-            }
-        })
-        .enumerate()
-    {
-        if i % 40 >= 19 && i % 40 - 19 == 0 {
-            sum += x * (i as isize + 1);
-        }
-        if !instruction.starts_with("noop") {
-            let (_, val) = instruction.split_once(" ").unwrap();
-            let val = val.parse::<isize>().unwrap();
-            x += val;
+    let mut values = vec![];
+    for instruction in input.lines() {
+        if instruction == "noop" {
+            values.push(x);
+            continue;
         }
+        let val = instruction
+            .strip_prefix("addx ")
+            .and_then(|val| val.parse::<isize>().ok())
+            .ok_or_else(|| ParseError::new(input, instruction, "noop or addx <value>"))?;
+        values.push(x);
+        values.push(x);
+        x += val;
     }
-    sum
+    return Ok(values);
 }
 
-pub fn part_2(input: &str) {
-    let mut x: isize = 1;
-    for (i, instruction) in input
-        .lines()
-        .flat_map(|instruction| {
-            if instruction.starts_with("addx") {
-                return vec!["noop", instruction];
-            } else {
-                return vec![instruction];
+pub fn part_1(input: &str) -> Result<isize> {
+    Day10::part_1(&Day10::parse(input)?)
+}
+
+pub fn part_2(input: &str) -> Result<String> {
+    Day10::part_2(&Day10::parse(input)?)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Parsed = Vec<isize>;
+    type Answer1 = isize;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(register_values(input)?)
+    }
+
+    fn part_1(values: &Self::Parsed) -> Result<Self::Answer1> {
+        Ok(values
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % 40 == 19)
+            .map(|(i, x)| x * (i as isize + 1))
+            .sum())
+    }
+
+    fn part_2(values: &Self::Parsed) -> Result<Self::Answer2> {
+        let mut screen = String::new();
+        for (i, &x) in values.iter().enumerate() {
+            if i % 40 == 0 {
+                screen.push('\n');
             }
-        })
-        .enumerate()
-    {
-        if i % 40 == 0 {
-            println!("");
-        }
-        let dif = x - (i % 40) as isize;
-        print!("{}", if dif >= -1 && dif <= 1 { "#" } else { "." });
-        if !instruction.starts_with("noop") {
-            let (_, val) = instruction.split_once(" ").unwrap();
-            let val = val.parse::<isize>().unwrap();
-            x += val;
+            let dif = x - (i % 40) as isize;
+            screen.push(if (-1..=1).contains(&dif) { '#' } else { '.' });
         }
+        return Ok(screen);
     }
-    println!("");
 }
 
 #[cfg(test)]
@@ -57,159 +69,23 @@ mod test {
     //     const INPUT: &'static str = "noop
     // addx 3
     // addx -5";
-    const INPUT: &'static str = "addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop";
+    const INPUT: &str = include_str!("../../examples/day10/example1.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT), 13140);
+        assert_eq!(part_1(INPUT).unwrap(), 13140);
     }
     #[test]
     fn test_part_2() {
-        part_2(INPUT)
+        assert_eq!(
+            part_2(INPUT).unwrap(),
+            "
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."
+        );
     }
 }
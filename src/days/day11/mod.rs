@@ -1,16 +1,19 @@
-use anyhow::{bail, Context, Result};
-use std::str::FromStr;
+use crate::solution::{Part, Solution, Variant};
+use crate::utill::parse_error::ParseError;
+use anyhow::{bail, Result};
+use std::{num::NonZeroUsize, str::FromStr};
 mod old_monkey;
 use old_monkey::OldMonkey;
 #[derive(Clone, Debug)]
 enum Chain {
     Add(usize),
     Mul(usize),
+    AddSelf,
     MulSelf,
 }
 
-#[derive(Debug)]
-struct Monkey {
+#[derive(Clone, Debug)]
+pub struct Monkey {
     item_worry_index: Vec<usize>,
     operation: Chain,
     test: usize,
@@ -19,63 +22,50 @@ struct Monkey {
     inspection_count: u128,
 }
 
+/// Finds the next line of a monkey block and returns what follows `split`
+/// on it, e.g. the items after `Starting items:`.
+fn field<'a>(
+    s: &'a str,
+    lines: &mut impl Iterator<Item = &'a str>,
+    split: &str,
+    expected: &str,
+) -> Result<&'a str, ParseError> {
+    let line = lines
+        .next()
+        .ok_or_else(|| ParseError::missing(s, expected))?;
+    let (_, rest) = line
+        .split_once(split)
+        .ok_or_else(|| ParseError::new(s, line, expected))?;
+    return Ok(rest.trim());
+}
+
 impl FromStr for Monkey {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut lines = s.lines().skip(1);
-        let (_, items) = lines
-            .next()
-            .context("can't find items")?
-            .split_once(":")
-            .context("can't split items")?;
+        let items = field(s, &mut lines, ":", "Starting items: <items>")?;
         let items = items
-            .split(",")
-            .map(|s| {
-                s.trim()
-                    .parse::<usize>()
-                    .ok()
-                    .context(format!("Can't parse number *{}*", s))
-            })
-            .collect::<Result<Vec<_>>>()?;
-        let (_, op) = lines
-            .next()
-            .context("can't parse op")?
-            .split_once("new = old ")
-            .context("can't split op")?;
-
-        let num: Option<usize> = op.trim()[1..].trim().parse().ok();
-
-        let (_, test) = lines
-            .next()
-            .context("can't parse test")?
-            .split_once("by ")
-            .context("can't split test")?;
-        let test = test.trim().parse::<usize>()?;
-        let (_, tr) = lines
-            .next()
-            .context("can't parse true")?
-            .split_once("monkey ")
-            .context("can't split true")?;
-        let tr = tr.trim().parse::<usize>()?;
-        let (_, fl) = lines
-            .next()
-            .context("can't parse false")?
-            .split_once("monkey ")
-            .context("can't split false")?;
-        let fl = fl.trim().parse::<usize>()?;
-
-        let operation: Chain = match num {
-            Some(num) => match &op.trim()[0..1] {
-                "+" => Chain::Add(num),
-                "*" => Chain::Mul(num),
-                _ => {
-                    bail!("Invalid op")
-                }
-            },
-            None => Chain::MulSelf,
+            .split(',')
+            .map(|item| ParseError::parse::<usize>(s, item.trim(), "a worry level"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let op = field(s, &mut lines, "new = old ", "Operation: new = old <op>")?;
+        let operand = op.get(1..).unwrap_or_default().trim();
+        let operation = match (op.get(0..1).unwrap_or_default(), operand) {
+            ("+", "old") => Chain::AddSelf,
+            ("*", "old") => Chain::MulSelf,
+            ("+", num) => Chain::Add(ParseError::parse(s, num, "a number or old")?),
+            ("*", num) => Chain::Mul(ParseError::parse(s, num, "a number or old")?),
+            _ => return Err(ParseError::new(s, op, "+ or * followed by a number or old")),
         };
 
+        let test = field(s, &mut lines, "by ", "Test: divisible by <number>")?;
+        let test = ParseError::parse::<NonZeroUsize>(s, test, "a divisor other than 0")?.get();
+        let tr = field(s, &mut lines, "monkey ", "If true: throw to monkey <number>")?;
+        let tr = ParseError::parse::<usize>(s, tr, "a monkey number")?;
+        let fl = field(s, &mut lines, "monkey ", "If false: throw to monkey <number>")?;
+        let fl = ParseError::parse::<usize>(s, fl, "a monkey number")?;
+
         Ok(Self {
             item_worry_index: items,
             test,
@@ -87,12 +77,32 @@ impl FromStr for Monkey {
     }
 }
 
-pub fn part_1(input: &str, calm: u128, rounds: usize) -> u128 {
-    let mut monkeys = input
+fn parse_monkeys(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    input
         .split("\n\n")
-        .map(|s| s.parse::<OldMonkey>().ok().context("Invalid Monkey"))
-        .collect::<Result<Vec<_>>>()
-        .unwrap();
+        .map(|s| s.parse::<Monkey>().map_err(|e| e.rebase(input, s)))
+        .collect()
+}
+
+/// Fails if any monkey throws to one that doesn't exist.
+fn check_targets(monkeys: &[Monkey]) -> Result<()> {
+    if let Some((m, monkey)) = monkeys
+        .iter()
+        .enumerate()
+        .find(|(_, monkey)| std::cmp::max(monkey.tr, monkey.fl) >= monkeys.len())
+    {
+        bail!(
+            "Monkey {} throws to missing monkey {}",
+            m,
+            std::cmp::max(monkey.tr, monkey.fl)
+        );
+    }
+    Ok(())
+}
+
+/// [`monkey_business`] through the boxed `f64` closures of [`OldMonkey`].
+fn old_monkey_business(monkeys: &[Monkey], calm: u128, rounds: usize) -> u128 {
+    let mut monkeys = monkeys.iter().map(OldMonkey::from).collect::<Vec<_>>();
     for _round in 0..rounds {
         for m in 0..monkeys.len() {
             let mut to_remove = vec![];
@@ -110,20 +120,49 @@ pub fn part_1(input: &str, calm: u128, rounds: usize) -> u128 {
             }
         }
     }
-    monkeys.sort_by(|a, b| b.inspection_count.partial_cmp(&a.inspection_count).unwrap());
+    monkeys.sort_by_key(|m| std::cmp::Reverse(m.inspection_count));
     monkeys
         .iter()
         .take(2)
         .fold(1, |acc, m| acc * m.inspection_count)
 }
 
-pub fn part_2(input: &str) -> u128 {
-    // collect monkeysj
-    let mut monkeys = input
-        .split("\n\n")
-        .map(|s| s.parse::<Monkey>())
-        .collect::<Result<Vec<_>>>()
-        .unwrap();
+/// Product of the two highest inspection counts after `rounds` rounds, worry
+/// levels being divided by `calm` after each inspection.
+fn monkey_business(monkeys: &[Monkey], calm: usize, rounds: usize) -> u128 {
+    let mut monkeys = monkeys.to_vec();
+    for _round in 0..rounds {
+        for m in 0..monkeys.len() {
+            // before part 2 builds its matrix these are the worry levels
+            let items = std::mem::take(&mut monkeys[m].item_worry_index);
+            monkeys[m].inspection_count += items.len() as u128;
+            for worry in items {
+                let worry = match monkeys[m].operation {
+                    Chain::Add(v) => worry + v,
+                    Chain::Mul(v) => worry * v,
+                    Chain::AddSelf => worry + worry,
+                    Chain::MulSelf => worry * worry,
+                } / calm;
+                let new_pos = if worry.is_multiple_of(monkeys[m].test) {
+                    monkeys[m].tr
+                } else {
+                    monkeys[m].fl
+                };
+                monkeys[new_pos].item_worry_index.push(worry);
+            }
+        }
+    }
+    monkeys.sort_by_key(|m| std::cmp::Reverse(m.inspection_count));
+    monkeys
+        .iter()
+        .take(2)
+        .fold(1, |acc, m| acc * m.inspection_count)
+}
+
+/// Ten thousand rounds without dividing, tracking each item's worry modulo
+/// every monkey's divisor.
+fn keep_away(monkeys: &[Monkey]) -> u128 {
+    let mut monkeys = monkeys.to_vec();
     // build matrix
     let mut counter = 0;
     let mut items = vec![];
@@ -137,7 +176,7 @@ pub fn part_2(input: &str) -> u128 {
     let mut matrix: Vec<Vec<usize>> = vec![items; monkeys.len()];
     for (mi, mv) in matrix.iter_mut().enumerate() {
         for mvv in mv.iter_mut() {
-            *mvv = *mvv % monkeys[mi].test;
+            *mvv %= monkeys[mi].test;
         }
     }
 
@@ -158,6 +197,10 @@ pub fn part_2(input: &str) -> u128 {
                             monkey_matrix[new_val_index] =
                                 (monkey_matrix[new_val_index] * v) % monkeys[mi].test
                         }
+                        Chain::AddSelf => {
+                            monkey_matrix[new_val_index] =
+                                (monkey_matrix[new_val_index] * 2) % monkeys[mi].test
+                        }
                         Chain::MulSelf => {
                             monkey_matrix[new_val_index] = (monkey_matrix[new_val_index]
                                 * monkey_matrix[new_val_index])
@@ -179,52 +222,79 @@ pub fn part_2(input: &str) -> u128 {
             }
         }
     }
-    monkeys.sort_by(|a, b| b.inspection_count.partial_cmp(&a.inspection_count).unwrap());
+    monkeys.sort_by_key(|m| std::cmp::Reverse(m.inspection_count));
     monkeys
         .iter()
         .take(2)
         .fold(1, |acc, m| acc * m.inspection_count)
 }
 
+pub fn part_1(input: &str, calm: usize, rounds: usize) -> Result<u128> {
+    Ok(monkey_business(&Day11::parse(input)?, calm, rounds))
+}
+
+pub fn part_2(input: &str) -> Result<u128> {
+    Day11::part_2(&Day11::parse(input)?)
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Parsed = Vec<Monkey>;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let monkeys = parse_monkeys(input)?;
+        check_targets(&monkeys)?;
+        Ok(monkeys)
+    }
+
+    fn part_1(monkeys: &Self::Parsed) -> Result<Self::Answer1> {
+        Ok(monkey_business(monkeys, 3, 20))
+    }
+
+    fn part_2(monkeys: &Self::Parsed) -> Result<Self::Answer2> {
+        Ok(keep_away(monkeys))
+    }
+
+    fn variants() -> Vec<Variant<Self::Parsed>> {
+        vec![Variant {
+            name: "OldMonkey",
+            part: Part::One,
+            run: |monkeys| Ok(old_monkey_business(monkeys, 3, 20).to_string()),
+        }]
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    const INPUT: &'static str = "Monkey 0:
-  Starting items: 79, 98
-  Operation: new = old * 19
-  Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
-
-Monkey 1:
-  Starting items: 54, 65, 75, 74
-  Operation: new = old + 6
-  Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
-
-Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
-
-Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1";
+    const INPUT: &str = include_str!("../../examples/day11/example1.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT, 3, 20), 10605);
+        assert_eq!(part_1(INPUT, 3, 20).unwrap(), 10605);
+        let monkeys = Day11::parse(INPUT).unwrap();
+        assert_eq!(old_monkey_business(&monkeys, 3, 20), 10605);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(INPUT), 2713310158);
+        assert_eq!(part_2(INPUT).unwrap(), 2713310158);
+    }
+
+    #[test]
+    fn test_zero_divisor() {
+        let input = INPUT.replacen("divisible by 23", "divisible by 0", 1);
+        let err = part_2(&input).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.line(), 4);
+        assert_eq!(err.columns(), 21..22);
+        assert_eq!(
+            err.to_string(),
+            "line 4, column 22: expected a divisor other than 0"
+        );
     }
 }
@@ -1,121 +1,172 @@
+use crate::solution::Solution;
+use crate::utill::grid::{Grid, Pos};
+use crate::utill::parse_error::ParseError;
+use crate::utill::search::{self, Search};
+use crate::utill::{chunk::ChunkIterator, v2d::Vec2D};
 use anyhow::{Context, Result};
-use std::collections::HashSet;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
-use std::{ops::Index, str::FromStr};
+use std::{fmt::Write, ops::Index, str::FromStr};
 
-type Cords = (usize, usize);
+type Cords = Pos;
 
-#[derive(Eq, PartialEq, Clone, Copy, Debug)]
-struct Node {
-    loc: Cords,
-    g: u32,
-    h: u32,
+/// Which steps between squares are allowed, the puzzle's by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// Most a single step can climb.
+    pub max_ascent: u8,
+    /// Most a single step can drop, `None` for any drop.
+    pub max_descent: Option<u8>,
+    /// Also step diagonally, to all 8 neighbours instead of 4.
+    pub diagonal: bool,
 }
 
-impl Add for Node {
-    type Output = Node;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::new_root((self.loc.0 + rhs.loc.0, self.loc.1 + rhs.loc.1))
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            max_ascent: 1,
+            max_descent: None,
+            diagonal: false,
+        }
     }
 }
 
-impl AddAssign for Node {
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs;
-    }
+#[derive(Debug, Clone)]
+pub struct Board {
+    heights: Grid<u8>,
+    start: Cords,
+    end: Cords,
+    rules: Rules,
 }
 
-impl Sub for Node {
-    type Output = Node;
+impl Board {
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self::new_root((self.loc.0 - rhs.loc.0, self.loc.1 - rhs.loc.1))
+    fn can_step(&self, from: Cords, to: Cords) -> bool {
+        let (from, to) = (self[from], self[to]);
+        if to > from {
+            return to - from <= self.rules.max_ascent;
+        }
+        return self.rules.max_descent.is_none_or(|max| from - to <= max);
     }
-}
-impl SubAssign for Node {
-    fn sub_assign(&mut self, rhs: Self) {
-        *self = *self - rhs;
+
+    fn neighbours(&self, loc: Cords) -> Box<dyn Iterator<Item = Cords> + '_> {
+        if self.rules.diagonal {
+            Box::new(self.heights.neighbours_8(loc))
+        } else {
+            Box::new(self.heights.neighbours_4(loc))
+        }
     }
-}
 
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.f().cmp(&self.f())
+    fn get_succesors(&self, loc: Cords) -> impl Iterator<Item = Cords> + '_ {
+        self.neighbours(loc)
+            .filter(move |&next| self.can_step(loc, next))
     }
-}
 
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+    /// Squares that can step onto `loc`, the climbing rule in reverse.
+    fn get_predecessors(&self, loc: Cords) -> impl Iterator<Item = Cords> + '_ {
+        self.neighbours(loc)
+            .filter(move |&prev| self.can_step(prev, loc))
     }
-}
 
-impl Node {
-    #[allow(dead_code)]
-    pub fn new(loc: Cords, g: u32, h: u32) -> Self {
-        Self { loc, g, h }
+    /// Breadth first search backwards from the end, stopping at the first
+    /// square `stop` accepts.
+    fn reverse_bfs(&self, mut stop: impl FnMut(Cords) -> bool) -> Search<Cords, usize> {
+        search::bfs(
+            [self.end],
+            |&loc| self.get_predecessors(loc),
+            |&loc| stop(loc),
+        )
     }
 
-    pub fn new_root(loc: Cords) -> Self {
-        Self { loc, g: 0, h: 0 }
+    /// Fewest steps from every square to the end, `None` where it can't be
+    /// reached.
+    pub fn distances_to_end(&self) -> Grid<Option<usize>> {
+        let search = self.reverse_bfs(|_| false);
+        let mut dist = Grid::new(self.heights.width(), self.heights.height(), None);
+        for (&loc, cost) in search.costs() {
+            dist[loc] = Some(cost);
+        }
+        return dist;
     }
 
-    fn f(&self) -> u32 {
-        self.g + self.h
+    /// Shortest path from `start` to the end, both included.
+    pub fn shortest_path(&self, start: Cords) -> Option<Vec<Cords>> {
+        search::a_star(
+            [start],
+            |&loc| self.get_succesors(loc).map(|next| (next, 1)),
+            |&loc| self.heuristic(loc),
+            |&loc| loc == self.end,
+        )
+        .goal_path()
     }
-}
 
-#[derive(Debug, Clone)]
-struct Board {
-    map: Vec<Vec<(u8, Option<Cords>)>>,
-    start: Cords,
-    end: Cords,
-}
+    /// Draws the board with `path` as arrows to each next step like the
+    /// puzzle's illustration, `E` at the end and `.` everywhere else. With
+    /// `color` the other squares show their height instead, every square on a
+    /// background shaded from green at `a` to white at `z`.
+    pub fn render(&self, path: &[Cords], color: bool) -> String {
+        let mut marks = self.heights.map(|_| None);
+        for [from, to] in path.iter().copied().windows::<2>() {
+            let step = Vec2D::from_pos(to) - Vec2D::from_pos(from);
+            let arrow = step.to_arrow().unwrap_or(match step {
+                Vec2D::UP_LEFT => '↖',
+                Vec2D::UP_RIGHT => '↗',
+                Vec2D::DOWN_LEFT => '↙',
+                _ => '↘',
+            });
+            marks[from] = Some(arrow);
+        }
+        marks[self.end] = Some('E');
 
-impl Board {
-    fn get_succesors(&self, q_node: Node) -> Vec<Node> {
-        let cv = self[q_node];
-        let mut successors = vec![];
-        let mut check = |node: Node, add: bool| {
-            let v_node = if add {
-                Some(q_node + node)
-            } else {
-                if q_node.loc.0 < node.loc.0 || q_node.loc.1 < node.loc.1 {
-                    None
-                } else {
-                    Some(q_node - node)
-                }
-            };
-            let v = v_node.map(|v_node| self.get(v_node));
-            match v {
-                Some(Some(&v)) => {
-                    if v <= cv + 1 {
-                        let mut new_node = v_node.unwrap();
-                        new_node.g = q_node.g + 1;
-                        // new_node.h = 0;
-                        successors.push(new_node);
-                    }
+        let mut out = String::new();
+        for (i, row) in marks.rows().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            for (j, mark) in row.iter().enumerate() {
+                if !color {
+                    out.push(mark.unwrap_or('.'));
+                    continue;
                 }
-                _ => {}
-            };
-        };
-        check(Node::new_root((1, 0)), true);
-        check(Node::new_root((1, 0)), false);
-        check(Node::new_root((0, 1)), true);
-        check(Node::new_root((0, 1)), false);
-        return successors;
-    }
-
-    fn get(&self, node: Node) -> Option<&u8> {
-        Some(&self.map.get(node.loc.0).map(|r| r.get(node.loc.1))??.0)
+                let height = self[(i, j)];
+                let shade = |low: u32| low + (255 - low) * height as u32 / 25;
+                let (r, g, b) = (shade(30), shade(110), shade(30));
+                let _ = match mark {
+                    Some(c) => write!(out, "\x1b[1;38;2;200;0;0;48;2;{};{};{}m{}", r, g, b, c),
+                    None => write!(
+                        out,
+                        "\x1b[22;38;2;0;0;0;48;2;{};{};{}m{}",
+                        r,
+                        g,
+                        b,
+                        (b'a' + height) as char
+                    ),
+                };
+            }
+            if color {
+                out.push_str("\x1b[0m");
+            }
+        }
+        return out;
     }
 
-    fn set_parent(&mut self, loc: Cords, parent: Cords) {
-        self.map[loc.0][loc.1].1 = Some(parent);
-    }
-    fn get_parent(&self, loc: Cords) -> Option<Cords> {
-        self.map[loc.0][loc.1].1
+    /// Lower bound on the steps left from `loc`: every step moves one square
+    /// and climbs at most `max_ascent`.
+    fn heuristic(&self, loc: Cords) -> u32 {
+        let (from, to) = (Vec2D::from_pos(loc), Vec2D::from_pos(self.end));
+        let distance = if self.rules.diagonal {
+            from.chebyshev(to)
+        } else {
+            from.manhattan(to)
+        };
+        let climb = self[self.end].saturating_sub(self[loc]);
+        let climbs = match self.rules.max_ascent {
+            0 => 0,
+            max => climb.div_ceil(max) as usize,
+        };
+        return std::cmp::max(distance, climbs) as u32;
     }
 }
 
@@ -123,171 +174,141 @@ impl Index<Cords> for Board {
     type Output = u8;
 
     fn index(&self, index: Cords) -> &Self::Output {
-        &self.map[index.0][index.1].0
-    }
-}
-
-impl Index<Node> for Board {
-    type Output = u8;
-
-    fn index(&self, index: Node) -> &Self::Output {
-        &self[index.loc]
+        &self.heights[index]
     }
 }
 
 impl FromStr for Board {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut start = None;
-        let mut end = None;
-        let map = s
-            .trim()
-            .lines()
-            .enumerate()
-            .map(|(y, s)| {
-                s.trim()
-                    .bytes()
-                    .enumerate()
-                    .map(|(x, b)| {
-                        if b == 'S' as u8 {
-                            start = Some((y, x));
-                            return (0u8, None);
-                        } else if b == 'E' as u8 {
-                            end = Some((y, x));
-                            return (('z' as u8) - ('a' as u8), None);
-                        } else {
-                            return (b - 'a' as u8, None);
-                        }
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
+        let map = Grid::parse_with(s, "a height a-z, S or E", |c| {
+            (c.is_ascii_lowercase() || c == 'S' || c == 'E').then_some(c)
+        })?;
+        let find = |target: char, expected: &str| {
+            map.iter()
+                .find(|(_, &c)| c == target)
+                .map(|(pos, _)| pos)
+                .ok_or_else(|| ParseError::missing(s, expected))
+        };
+        let start = find('S', "a start S")?;
+        let end = find('E', "an end E")?;
+        let heights = map.map(|&c| match c {
+            'S' => 0,
+            'E' => b'z' - b'a',
+            c => c as u8 - b'a',
+        });
         return Ok(Self {
-            map,
-            start: start.context("couldn't find start")?,
-            end: end.context("couldn't find end")?,
+            heights,
+            start,
+            end,
+            rules: Rules::default(),
         });
     }
 }
 
-fn a_star(mut board: Board, found: Option<&mut HashSet<Cords>>) -> Option<usize> {
-    // println!("{:?}", board);
-    let mut open: Vec<Node> = vec![Node::new_root(board.start)];
-    let mut closed: Vec<Node> = vec![];
-    let mut has_path = false;
-
-    'top: while let Some(q) = open.pop() {
-        // println!("  f(q): {:?}", q.f());
-        let successors = board.get_succesors(q);
-        for suc in successors.iter() {
-            // println!("    suc: {:?}", suc);
-            if suc.loc == board.end {
-                println!("Found the END!!!");
-                board.set_parent(suc.loc, q.loc);
-                has_path = true;
-                break 'top;
-            }
-            if closed
-                .iter()
-                .find(|n| n.loc == suc.loc)
-                .map_or_else(|| false, |_| true)
-            {
-                continue;
-            }
-            if let Some(n) = open
-                .iter_mut()
-                .find(|n| n.loc == suc.loc && n.f() <= suc.f())
-            {
-                n.g = suc.g;
-                n.h = suc.h;
-            } else {
-                open.push(*suc);
-            }
-            board.set_parent(suc.loc, q.loc);
-        }
-        closed.push(q);
-        open.sort_by(|a, b| a.cmp(b));
-        // println!("o: {:?}", open);
-    }
-    if !has_path {
-        return None;
+pub fn part_1(input: &str) -> Result<usize> {
+    Day12::part_1(&Day12::parse(input)?)
+}
+
+pub fn part_2(input: &str) -> Result<usize> {
+    Day12::part_2(&Day12::parse(input)?)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Parsed = Board;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(input.parse()?)
     }
-    let mut node = board.get_parent(board.end);
-    let mut any_start = false;
-    if let Some(found) = found {
-        any_start = true;
-        while let Some(p) = node {
-            if board[p] == 0 {
-                found.insert(p);
-            }
-            node = board.get_parent(p);
-        }
-        node = board.get_parent(board.end);
+
+    fn part_1(board: &Self::Parsed) -> Result<Self::Answer1> {
+        let path = board.shortest_path(board.start).context("No path from start to end")?;
+        return Ok(path.len() - 1);
     }
-    let mut count = 0;
-    // println!("starting search {:?} until {:?}", node, board.start);
-    // print!("{:?} -> ", board.end);
-    while let Some(p) = node {
-        count += 1;
-        if (any_start && board[p] == 0) || (!any_start && p == board.start) {
-            println!("END_found = {:?}", p);
-            return Some(count);
-        }
-        // print!("{:?} -> ", p);
-        node = board.get_parent(p);
+
+    fn part_2(board: &Self::Parsed) -> Result<Self::Answer2> {
+        // the closest lowest square is the first one found walking back
+        return board
+            .reverse_bfs(|loc| board[loc] == 0)
+            .goal_cost()
+            .context("No path from any lowest square to end");
     }
-    println!("no path found");
-    return None;
-}
 
-pub fn part_1(input: &str) -> usize {
-    let board: Board = input.parse().unwrap();
-    return a_star(board, None).unwrap();
-}
-pub fn part_2(input: &str) -> usize {
-    let board: Board = input.parse().unwrap();
-    let mut min_val = usize::MAX;
-    let mut found = HashSet::new();
-
-    for y in 0..board.map.len() {
-        for x in 0..board.map[y].len() {
-            if board.map[y][x].0 != 0 || found.contains(&(y, x)) {
-                continue;
-            }
-            let mut b = board.clone();
-            b.start = (y, x);
-            let cur = a_star(b, Some(&mut found));
-            if let None = cur {
-                continue;
-            }
-            let cur = cur.unwrap();
-            println!("found: {}, cur: {}", found.len(), cur);
-            if cur < min_val {
-                min_val = cur;
-            }
-        }
+    fn visualize(board: &Self::Parsed, color: bool) -> Result<Option<String>> {
+        let path = board.shortest_path(board.start).context("No path from start to end")?;
+        return Ok(Some(board.render(&path, color)));
     }
-    return min_val;
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi";
+    const INPUT: &str = include_str!("../../examples/day12/example1.txt");
 
-    #[ignore]
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT), 31);
+        assert_eq!(part_1(INPUT).unwrap(), 31);
     }
-    #[ignore]
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(INPUT), 29);
+        assert_eq!(part_2(INPUT).unwrap(), 29);
+    }
+
+    #[test]
+    fn test_distances_to_end() {
+        let board = Day12::parse(INPUT).unwrap();
+        let dist = board.distances_to_end();
+        assert_eq!(dist[board.end], Some(0));
+        assert_eq!(dist[board.start], Some(31));
+        assert_eq!(dist[(4, 0)], Some(29));
+    }
+
+    #[test]
+    fn test_render() {
+        let board = Day12::parse(INPUT).unwrap();
+        let path = board.shortest_path(board.start).unwrap();
+        let expected = "\
+v..v<<<<
+>v.vv<<^
+.>vv>E^^
+..v>>>^^
+..>>>>>^";
+        assert_eq!(board.render(&path, false), expected);
+        assert!(board.render(&path, true).contains("\x1b["));
+    }
+
+    #[test]
+    fn test_rules() {
+        let board = Day12::parse(INPUT).unwrap();
+        let steps = |rules| {
+            let board = board.clone().with_rules(rules);
+            board.shortest_path(board.start).map(|path| path.len() - 1)
+        };
+        assert_eq!(steps(Rules::default()), Some(31));
+        let climb_2 = Rules {
+            max_ascent: 2,
+            ..Rules::default()
+        };
+        assert_eq!(steps(climb_2), Some(27));
+        let diagonal = Rules {
+            diagonal: true,
+            ..Rules::default()
+        };
+        assert_eq!(steps(diagonal), Some(27));
+        let no_drops = Rules {
+            max_descent: Some(0),
+            ..Rules::default()
+        };
+        assert_eq!(steps(no_drops), Some(31));
+        let no_climbing = Rules {
+            max_ascent: 0,
+            ..Rules::default()
+        };
+        assert_eq!(steps(no_climbing), None);
     }
 }
@@ -1,4 +1,6 @@
-use anyhow::{bail, Context, Result};
+use crate::solution::Solution;
+use crate::utill::parse_error::ParseError;
+use anyhow::Result;
 use std::{cmp::Ordering, str::FromStr};
 
 #[derive(Clone, Debug)]
@@ -7,54 +9,54 @@ enum El {
     Val(i32),
 }
 
+/// A pair of packets.
 #[derive(Clone, Debug)]
-struct Lists {
+pub struct Lists {
     right: El,
     left: El,
 }
 impl FromStr for El {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self> {
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         use El::{List, Val};
 
-        fn recursive_from_str(sl: &str) -> Result<(El, usize)> {
+        fn recursive_from_str(s: &str, sl: &str) -> Result<(El, usize), ParseError> {
             let mut root = Vec::new();
             let mut num: Option<(usize, usize)> = None;
             let mut i = 0;
-            while i < sl.len() {
+            let bytes = sl.as_bytes();
+            while i < bytes.len() {
                 // println!("root: {:?}", root);
-                let c = sl.chars().nth(i).context("Invalid index")?;
-                match c {
-                    '[' => {
+                match bytes[i] {
+                    b'[' => {
                         // println!("new");
-                        let (n_node, sk) = recursive_from_str(&sl[i + 1..])?;
+                        let (n_node, sk) = recursive_from_str(s, &sl[i + 1..])?;
                         root.push(n_node);
                         i += sk + 1;
                     }
-                    ']' => {
+                    b']' => {
                         // println!("end");
                         if let Some(num) = num {
-                            let v = sl[num.0..num.1].parse::<i32>()?;
+                            let v = ParseError::parse::<i32>(s, &sl[num.0..num.1], "a number")?;
                             root.push(Val(v));
                         }
                         return Ok((List(root), i));
                     }
-                    ',' => {
+                    b',' => {
                         // println!("comma");
-                        if !(i == 0 || sl.chars().nth(i - 1).unwrap() == ']') {
+                        if !(i == 0 || bytes[i - 1] == b']') {
                             // println!("comma number");
                             if let Some(num) = num {
                                 // println!("comma makes sense, num = {:?}", num);
-                                let v = sl[num.0..num.1].parse::<i32>()?;
+                                let v = ParseError::parse::<i32>(s, &sl[num.0..num.1], "a number")?;
                                 root.push(Val(v));
                                 // println!("root post push: {:?}", root);
                             }
                         }
                         num = None;
                     }
-                    _ => {
-                        // println!("val {}", c);
+                    b'0'..=b'9' | b'-' => {
                         match &mut num {
                             Some(num) => {
                                 num.1 = i + 1;
@@ -67,23 +69,42 @@ impl FromStr for El {
                         }
                         // println!("num after{:?}", num);
                     }
+                    _ => {
+                        // everything before was ascii so `i` is a char boundary
+                        let c = sl[i..].chars().next().unwrap_or_default();
+                        let token = &sl[i..i + c.len_utf8()];
+                        return Err(ParseError::new(s, token, "a number, [, ] or ,"));
+                    }
                 };
                 i += 1;
             }
-            bail!("Invalid: expected ]");
+            return Err(ParseError::missing(s, "]"));
         }
-        let (root, _) = recursive_from_str(&s[1..])?;
+        let rest = s
+            .strip_prefix('[')
+            .ok_or_else(|| {
+                let first = s.chars().next().map_or(0, char::len_utf8);
+                ParseError::new(s, &s[..first], "[")
+            })?;
+        let (root, _) = recursive_from_str(s, rest)?;
         return Ok(root);
     }
 }
 
 impl FromStr for Lists {
-    type Err = anyhow::Error;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines().take(2).map(str::parse);
+        let mut lines = s
+            .lines()
+            .take(2)
+            .map(|ln| ln.parse().map_err(|e: ParseError| e.rebase(s, ln)));
         return Ok(Self {
-            left: lines.next().context("Can't find first list")??,
-            right: lines.next().context("Can't find second list")??,
+            left: lines
+                .next()
+                .ok_or_else(|| ParseError::missing(s, "a first list"))??,
+            right: lines
+                .next()
+                .ok_or_else(|| ParseError::missing(s, "a second list"))??,
         });
     }
 }
@@ -150,103 +171,115 @@ fn recursive_compare(lsts: &Lists) -> State {
     }
 }
 
-pub fn part_1(input: &str) -> usize {
-    input
-        .split("\n\n")
-        .enumerate()
-        .filter_map(|(i, lsts)| {
-            let lsts: Lists = lsts.parse().unwrap();
+pub fn part_1(input: &str) -> Result<usize> {
+    Day13::part_1(&Day13::parse(input)?)
+}
+
+pub fn part_2(input: &str) -> Result<usize> {
+    Day13::part_2(&Day13::parse(input)?)
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Parsed = Vec<Lists>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(input
+            .split("\n\n")
+            .map(|lsts| lsts.parse::<Lists>().map_err(|e| e.rebase(input, lsts)))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part_1(pairs: &Self::Parsed) -> Result<Self::Answer1> {
+        let mut sum = 0;
+        for (i, parsed) in pairs.iter().enumerate() {
             // println!("comparing : {}", i + 1);
-            match recursive_compare(&lsts) {
+            match recursive_compare(parsed) {
                 State::Good => {
                     // println!("good: {}", i + 1);
-                    return Some(i + 1);
+                    sum += i + 1;
                 }
-                State::Bad => None,
-                State::Continue => None,
+                State::Bad => {}
+                State::Continue => {}
             }
-        })
-        .sum()
-}
-
-pub fn part_2(input: &str) -> usize {
-    use El::{List, Val};
-    let mut input = input
-        .lines()
-        .filter(|s| !s.is_empty())
-        .map(str::parse)
-        .collect::<Result<Vec<El>>>()
-        .expect("All inputs should be valid");
-    input.push(List(vec![List(vec![Val(2)])]));
-    input.push(List(vec![List(vec![Val(6)])]));
-    input.sort_by(|a, b| {
-        match recursive_compare(&Lists {
-            left: a.clone(),
-            right: b.clone(),
-        }) {
-            State::Good => Ordering::Less,
-            State::Bad => Ordering::Greater,
-            State::Continue => unreachable!("TF?"),
         }
-    });
-    let mut a = 0;
-    let mut b = 0;
-    for (i, v) in input.into_iter().enumerate() {
-        if let List(o) = v {
-            if o.len() != 1 {
-                continue;
+        Ok(sum)
+    }
+
+    fn part_2(pairs: &Self::Parsed) -> Result<Self::Answer2> {
+        use El::{List, Val};
+        let mut input = pairs
+            .iter()
+            .flat_map(|lsts| [lsts.left.clone(), lsts.right.clone()])
+            .collect::<Vec<El>>();
+        input.push(List(vec![List(vec![Val(2)])]));
+        input.push(List(vec![List(vec![Val(6)])]));
+        input.sort_by(|a, b| {
+            match recursive_compare(&Lists {
+                left: a.clone(),
+                right: b.clone(),
+            }) {
+                State::Good => Ordering::Less,
+                State::Bad => Ordering::Greater,
+                State::Continue => Ordering::Equal,
             }
-            if let List(inn) = &o[0] {
-                if inn.len() != 1 {
+        });
+        let mut a = 0;
+        let mut b = 0;
+        for (i, v) in input.into_iter().enumerate() {
+            if let List(o) = v {
+                if o.len() != 1 {
                     continue;
                 }
-                if let Val(x) = inn[0] {
-                    if x == 2 {
-                        a = i + 1;
+                if let List(inn) = &o[0] {
+                    if inn.len() != 1 {
+                        continue;
                     }
-                    if x == 6 {
-                        b = i + 1;
+                    if let Val(x) = inn[0] {
+                        if x == 2 {
+                            a = i + 1;
+                        }
+                        if x == 6 {
+                            b = i + 1;
+                        }
                     }
                 }
             }
         }
+        Ok(a * b)
     }
-    a * b
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "[1,1,3,1,1]
-[1,1,5,1,1]
-
-[[1],[2,3,4]]
-[[1],4]
-
-[9]
-[[8,7,6]]
-
-[[4,4],4,4]
-[[4,4],4,4,4]
-
-[7,7,7,7]
-[7,7,7]
-
-[]
-[3]
-
-[[[]]]
-[[]]
-
-[1,[2,[3,[4,[5,6,7]]]],8,9]
-[1,[2,[3,[4,[5,6,0]]]],8,9]";
+    const INPUT: &str = include_str!("../../examples/day13/example1.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT), 13);
+        assert_eq!(part_1(INPUT).unwrap(), 13);
     }
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(INPUT), 140);
+        assert_eq!(part_2(INPUT).unwrap(), 140);
+    }
+    #[test]
+    fn test_bad_packet() {
+        let err = part_1("[1,2]\n[3,4]\n\n[1,[2,x]]\n[1]").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.line(), 4);
+        assert_eq!(err.columns(), 6..7);
+        let err = part_2("[1,2]\n[3,4").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 5: expected ]");
+
+        let err = part_1("[1,2]\n[3,[é]]").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.to_string(), "line 2, columns 5-6: expected a number, [, ] or ,");
+        let err = part_1("é[1]\n[1]").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, columns 1-2: expected [");
     }
 }
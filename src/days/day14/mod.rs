@@ -1,201 +1,198 @@
-use std::{collections::BinaryHeap, str::FromStr};
+use crate::solution::Solution;
+use crate::utill::grid::{Cell, Grid, Pos};
+use crate::utill::parse_error::ParseError;
+use crate::utill::v2d::Vec2D;
+use std::str::FromStr;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Result};
 
-#[derive(Debug)]
-struct Cave {
-    rocks: Vec<Vec<usize>>,
-    source: (usize, usize),
-    largest: usize,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Air,
+    Rock,
+    Sand,
+}
+
+impl Cell for Tile {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Tile::Air),
+            '#' => Some(Tile::Rock),
+            'o' => Some(Tile::Sand),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Air => '.',
+            Tile::Rock => '#',
+            Tile::Sand => 'o',
+        }
+    }
+}
+
+/// The cave as a grid of tiles, `left` being the x coordinate of its first
+/// column. It is wide enough for the sand pile resting on the floor.
+#[derive(Debug, Clone)]
+pub struct Cave {
+    tiles: Grid<Tile>,
+    left: usize,
+    source: Pos,
+    floor: usize,
 }
 
 impl Cave {
+    /// Draws the cave between the `(x, y)` corners `start` and `end`.
     pub fn draw(&self, start: (usize, usize), end: (usize, usize)) -> String {
         let mut s = "".to_string();
 
-        for x in start.1..=end.1 {
+        for y in start.1..=end.1 {
             let mut row = "".to_string();
-            for y in start.0..=end.0 {
-                if (x, y) == self.source {
-                    row = format!("{}+", row);
-                } else if let Some(_) = self.rocks[y].iter().find(|&&xi| xi == x) {
-                    row = format!("{}#", row);
+            for x in start.0..=end.0 {
+                let pos = (y, x.wrapping_sub(self.left));
+                if pos == self.source {
+                    row.push('+');
                 } else {
-                    row = format!("{}.", row);
+                    row.push(self.tiles.get(pos).unwrap_or(&Tile::Air).to_char());
                 }
             }
             s = format!("{}\n{}", s, row);
         }
         return s;
     }
-    pub fn simulate_part_2(&mut self) -> usize {
+    pub fn simulate_part_2(&mut self) -> Result<usize> {
         let mut counter = 0;
-        while let Some(l) = self.drop(self.source, true) {
-            // can be optomized, im not going to this can be an insertion sort which will be ~O(N)
-            // cause of mostly sorted
-            self.rocks[l.0].push(l.1);
-            self.rocks[l.0].sort();
+        while let Some(l) = self.drop(true) {
+            self.tiles[l] = Tile::Sand;
             counter += 1;
             if l == self.source {
-                return counter;
+                return Ok(counter);
             }
         }
-        unreachable!("Error in Cave");
+        bail!("Sand fell out of the cave before blocking the source");
     }
     pub fn simulate_part_1(&mut self) -> usize {
         let mut counter = 0;
-        while let Some(l) = self.drop(self.source, false) {
-            // can be optomized, im not going to this can be an insertion sort which will be ~O(N)
-            // cause of mostly sorted
-            self.rocks[l.0].push(l.1);
-            self.rocks[l.0].sort();
+        while let Some(l) = self.drop(false) {
+            self.tiles[l] = Tile::Sand;
             counter += 1;
         }
         return counter;
     }
 
-    fn drop(&mut self, from: (usize, usize), has_floor: bool) -> Option<(usize, usize)> {
-        let pnt = *self.rocks[from.0].iter().find(|pot| {
-            if **pot > from.1 {
-                return true;
-            }
-            return false;
-        })?;
-        if pnt == self.largest && !has_floor {
+    /// Where the next unit of sand comes to rest, `None` if it falls into the
+    /// abyss (or out of the grid).
+    fn drop(&self, has_floor: bool) -> Option<Pos> {
+        let mut pos = self.source;
+        if self.tiles[pos] != Tile::Air {
             return None;
         }
-
-        // check if it can go left
-        // let l_list = self.rocks.get(from.0 - 1)?; // might underflow?
-
-        let l_list = match (has_floor, self.rocks.get(from.0 - 1)) {
-            (_, Some(v)) => Some(v),
-            (true, None) => {
-                self.rocks.insert(from.0 - 1, vec![self.largest]);
-                Some(&self.rocks[from.0 - 1])
-            }
-            (false, None) => None,
-        }?;
-        let mut no_left = false;
-        for y in l_list.iter() {
-            if *y == pnt {
-                no_left = true;
-                break;
-            }
-        }
-        if !no_left {
-            return self.drop((from.0 - 1, pnt), has_floor);
-        }
-        let r_list = match (has_floor, self.rocks.get(from.0 + 1)) {
-            (_, Some(v)) => Some(v),
-            (true, None) => {
-                self.rocks.insert(from.0 + 1, vec![self.largest]);
-                Some(&self.rocks[from.0 + 1])
+        loop {
+            if pos.0 + 1 == self.floor {
+                return has_floor.then_some(pos);
             }
-            (false, None) => None,
-        }?;
-        let mut no_right = false;
-        for y in r_list.iter() {
-            if *y == pnt {
-                no_right = true;
-                break;
+            let next = [0, -1, 1]
+                .into_iter()
+                .map(|dx| self.tiles.step(pos, Vec2D(dx, 1)))
+                .find(|next| next.is_none_or(|next| self.tiles[next] == Tile::Air));
+            match next {
+                Some(Some(next)) => pos = next,
+                Some(None) => return None,
+                None => return Some(pos),
             }
         }
-        if !no_right {
-            return self.drop((from.0 + 1, pnt), has_floor);
-        }
-
-        return Some((from.0, pnt - 1));
     }
 }
 
 impl FromStr for Cave {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut x_vec = vec![BinaryHeap::new(); 1000];
-        let mut largest = 0;
-        for lines in s.lines() {
-            for window in lines
-                .split(" -> ")
-                .map(|point| {
-                    let (x, y) = point.split_once(",").context("Invalid Point")?;
-                    Ok((x.parse::<usize>()?, y.parse::<usize>()?))
-                })
-                .collect::<Result<Vec<_>>>()?
-                .as_slice()
-                .windows(2)
-            {
-                let start = window[0];
-                let end = window[1];
-                if start.0 == end.0 {
-                    if x_vec.len() <= start.0 {
-                        x_vec.resize(start.0 + 1, BinaryHeap::new());
-                    }
-                    let (s, e) = if start.1 > end.1 {
-                        (end.1, start.1)
-                    } else {
-                        (start.1, end.1)
-                    };
-                    for v in (s..=e).rev() {
-                        x_vec[start.0].push(v);
-                        largest = std::cmp::max(v, largest);
-                    }
-                    continue;
-                }
-                let (s, e) = if start.0 > end.0 {
-                    (end.0, start.0)
-                } else {
-                    (start.0, end.0)
-                };
-                for v in s..=e {
-                    if x_vec.len() <= v {
-                        x_vec.resize(v + 1, BinaryHeap::new());
+        let paths = s
+            .lines()
+            .map(|line| {
+                line.split(" -> ")
+                    .map(|point| {
+                        let (x, y) = point
+                            .split_once(',')
+                            .ok_or_else(|| ParseError::new(s, point, "a point x,y"))?;
+                        Ok((
+                            ParseError::parse::<usize>(s, x, "an x coordinate")?,
+                            ParseError::parse::<usize>(s, y, "a y coordinate")?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, ParseError>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let points = paths.iter().flatten();
+        let floor = points.clone().map(|p| p.1).max().unwrap_or(0) + 2;
+        // sand spreads at most `floor` to each side of the source
+        let left = points.clone().map(|p| p.0).min().unwrap_or(500);
+        let left = std::cmp::min(left, 500usize.saturating_sub(floor + 1));
+        let right = points.map(|p| p.0).max().unwrap_or(500);
+        let right = std::cmp::max(right, 500 + floor + 1);
+
+        let mut tiles = Grid::new(right - left + 1, floor + 1, Tile::Air);
+        for path in paths.iter() {
+            for window in path.windows(2) {
+                let (start, end) = (window[0], window[1]);
+                for x in start.0.min(end.0)..=start.0.max(end.0) {
+                    for y in start.1.min(end.1)..=start.1.max(end.1) {
+                        tiles[(y, x - left)] = Tile::Rock;
                     }
-                    x_vec[v].push(start.1);
-                    largest = std::cmp::max(start.1, largest);
                 }
             }
         }
-        largest += 2;
-        let mut x_vec = x_vec
-            .into_iter()
-            .map(|bh| bh.into_sorted_vec())
-            .collect::<Vec<_>>();
-
-        for x in x_vec.iter_mut() {
-            x.push(largest);
-        }
 
         return Ok(Self {
-            source: (500, 0),
-            rocks: x_vec,
-            largest,
+            tiles,
+            left,
+            source: (0, 500 - left),
+            floor,
         });
     }
 }
 
-pub fn part_1(input: &str) -> usize {
-    let mut cave: Cave = input.parse().unwrap();
-    return cave.simulate_part_1();
+pub fn part_1(input: &str) -> Result<usize> {
+    Day14::part_1(&Day14::parse(input)?)
+}
+
+pub fn part_2(input: &str) -> Result<usize> {
+    Day14::part_2(&Day14::parse(input)?)
 }
-pub fn part_2(input: &str) -> usize {
-    let mut cave: Cave = input.parse().unwrap();
-    return cave.simulate_part_2();
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Parsed = Cave;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(input.parse()?)
+    }
+
+    fn part_1(cave: &Self::Parsed) -> Result<Self::Answer1> {
+        return Ok(cave.clone().simulate_part_1());
+    }
+
+    fn part_2(cave: &Self::Parsed) -> Result<Self::Answer2> {
+        return cave.clone().simulate_part_2();
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
+    const INPUT: &str = include_str!("../../examples/day14/example1.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT), 24);
+        assert_eq!(part_1(INPUT).unwrap(), 24);
     }
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(INPUT), 93);
+        assert_eq!(part_2(INPUT).unwrap(), 93);
     }
 }
@@ -1,10 +1,27 @@
+use crate::solution::{ImageFormat, Solution};
+use crate::utill::{grid::Grid, intervals::IntervalSet, parse_error::ParseError};
 use anyhow::{Context, Result};
-use std::str::FromStr;
+use std::{collections::HashSet, ops::RangeInclusive, str::FromStr};
+
+/// `(x, y)` position, `y` growing downwards.
+pub type Point = (isize, isize);
+
+/// Inclusive `(x, y)` ranges of a rectangle to search.
+pub type Region = (RangeInclusive<isize>, RangeInclusive<isize>);
+
+/// Row checked by part 1.
+const ROW: isize = 2000000;
+/// Largest coordinate searched by part 2, also the tuning frequency multiplier.
+const MAX: isize = 4000000;
+/// Size of the picture of the whole part 2 region, in characters for
+/// `--visualize` and in pixels for `--image`.
+const PICTURE: (usize, usize) = (80, 40);
+const IMAGE: (usize, usize) = (500, 500);
 
 #[derive(Debug)]
 struct Sensor {
-    pos: (isize, isize),
-    closest_beacon: (isize, isize),
+    pos: Point,
+    closest_beacon: Point,
 }
 
 impl Sensor {
@@ -14,177 +31,378 @@ impl Sensor {
     }
 }
 impl FromStr for Sensor {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self> {
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         let v = s
-            .split(":")
+            .split(':')
             .map(|v| {
-                let (x, y) = v.split_once(",").context("Invalid Str")?;
-                let (_, x) = x.split_once("x=").context("Can't find x")?;
-                let (_, y) = y.split_once("y=").context("Can't find x")?;
-                let x = x.parse()?;
-                let y = y.parse()?;
+                let (x, y) = v
+                    .split_once(',')
+                    .ok_or_else(|| ParseError::new(s, v, "x=<x>, y=<y>"))?;
+                let (_, x) = x
+                    .split_once("x=")
+                    .ok_or_else(|| ParseError::new(s, x, "x=<x>"))?;
+                let (_, y) = y
+                    .split_once("y=")
+                    .ok_or_else(|| ParseError::new(s, y, "y=<y>"))?;
+                let x = ParseError::parse(s, x, "an x coordinate")?;
+                let y = ParseError::parse(s, y, "a y coordinate")?;
                 return Ok((x, y));
             })
-            .collect::<Result<Vec<_>>>()?;
-        Ok(Self {
-            pos: v[0],
-            closest_beacon: v[1],
-        })
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        match v[..] {
+            [pos, closest_beacon] => Ok(Self {
+                pos,
+                closest_beacon,
+            }),
+            _ => Err(ParseError::new(s, s, "a sensor and its closest beacon")),
+        }
     }
 }
 
-fn find_slice(input: &str, y_slice: isize) -> usize {
-    let mut left_bound = isize::MAX;
-    let mut right_bound = isize::MIN;
-    let sensors: Vec<Sensor> = input
-        .lines()
-        .map(str::parse)
-        .collect::<Result<Vec<_>>>()
-        .unwrap();
-    for s in sensors.iter() {
-        let center_dist = s.distance() - (s.pos.1 - y_slice).abs();
-        let cur_left = s.pos.0 - center_dist;
-        let cur_right = s.pos.0 + center_dist;
-        left_bound = std::cmp::min(cur_left, left_bound);
-        right_bound = std::cmp::max(cur_right, right_bound);
-    }
-    return (right_bound - left_bound) as usize;
+/// Every sensor with the beacon closest to it.
+#[derive(Debug)]
+pub struct SensorField {
+    sensors: Vec<Sensor>,
+}
+
+impl FromStr for SensorField {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let sensors = s
+            .lines()
+            .map(|line| line.parse().map_err(|e: ParseError| e.rebase(s, line)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { sensors })
+    }
 }
 
-fn find_free(input: &str, max_c: isize) -> usize {
-    let mut bounds: Vec<Vec<(isize, isize)>> = vec![vec![]; max_c as usize + 1];
-    let sensors: Vec<Sensor> = input
-        .lines()
-        .map(str::parse)
-        .collect::<Result<Vec<_>>>()
-        .unwrap();
-
-    for y_slice in 0..(max_c as usize) {
-        println!("checking slice {y_slice}/{max_c}");
-        for s in sensors.iter() {
-            let true_dist = s.distance();
-            if (s.pos.1 - y_slice as isize).abs() > true_dist {
-                // println!("skipping: {s:?}");
-                continue;
+impl SensorField {
+    /// Columns of row `y` within reach of some sensor.
+    fn covered_row(&self, y: isize) -> IntervalSet<isize> {
+        let mut covered = IntervalSet::new();
+        self.fill_row(y, &mut covered);
+        covered
+    }
+
+    /// [`Self::covered_row`] into `covered`, reusing its allocation.
+    fn fill_row(&self, y: isize, covered: &mut IntervalSet<isize>) {
+        covered.refill(self.sensors.iter().filter_map(|s| {
+            let center_dist = s.distance() - (s.pos.1 - y).abs();
+            (center_dist >= 0).then(|| s.pos.0 - center_dist..s.pos.0 + center_dist + 1)
+        }));
+    }
+
+    /// Columns of row `y` within reach of some sensor, sorted and merged so
+    /// no two ranges overlap or touch.
+    pub fn coverage_on_row(&self, y: isize) -> Vec<RangeInclusive<isize>> {
+        self.covered_row(y)
+            .iter()
+            .map(|range| range.start..=range.end - 1)
+            .collect()
+    }
+
+    /// Positions of row `y` that can't hold a beacon, those a sensor covers
+    /// minus the beacons already known to be there.
+    pub fn no_beacon_count(&self, y: isize) -> usize {
+        let covered = self.covered_row(y).total_len() as usize;
+        let beacons = self
+            .sensors
+            .iter()
+            .map(|s| s.closest_beacon)
+            .filter(|beacon| beacon.1 == y)
+            .collect::<HashSet<_>>();
+        return covered - beacons.len();
+    }
+
+    /// The first position of `region`, row by row, that no sensor covers.
+    pub fn find_uncovered(&self, (xs, ys): Region) -> Option<Point> {
+        let mut covered = IntervalSet::new();
+        for y in ys {
+            self.fill_row(y, &mut covered);
+            let first = covered
+                .complement_iter(*xs.start()..*xs.end() + 1)
+                .next()
+                .map(|range| range.start);
+            if let Some(x) = first {
+                return Some((x, y));
             }
-            let center_dist = (true_dist - (s.pos.1 - y_slice as isize).abs()).abs();
-            let cur_bounds = (s.pos.0 - center_dist, s.pos.0 + center_dist);
-
-            // if y_slice == 11 {
-            // println!(
-            //     "pos: {:?}, true_distance: {:?}, center_dist: {:?}, cur_bouunds: {:?}",
-            //     s.pos, true_dist, center_dist, cur_bounds
-            // );
-            // }
-
-            bounds[y_slice].push(cur_bounds);
-            // try to combine things in bounds
-            'w_loop: while bounds[y_slice].len() > 1 {
-                for i in 0..bounds[y_slice].len() {
-                    for j in 0..bounds[y_slice].len() {
-                        if i == j {
-                            continue;
-                        }
-                        // println!("i: {:?}, j: {:?}", bounds[y_slice][i], bounds[y_slice][j]);
-                        // check if i is inside j
-                        if bounds[y_slice][i].0 >= bounds[y_slice][j].0
-                            && bounds[y_slice][i].1 <= bounds[y_slice][j].1
-                        {
-                            // println!(
-                            //     "{:?} is inside {:?}",
-                            //     bounds[y_slice][i], bounds[y_slice][j]
-                            // );
-                            bounds[y_slice].remove(i);
-                            // println!("bounds[{:?}] = {:?}", y_slice, bounds[y_slice]);
-                            continue 'w_loop;
-                        }
-                        // check if j is inside i
-                        if bounds[y_slice][j].0 >= bounds[y_slice][i].0
-                            && bounds[y_slice][j].1 <= bounds[y_slice][i].1
-                        {
-                            // println!(
-                            //     "{:?} is inside {:?}",
-                            //     bounds[y_slice][j], bounds[y_slice][i]
-                            // );
-                            bounds[y_slice].remove(j);
-                            // println!("bounds[{:?}] = {:?}", y_slice, bounds[y_slice]);
-                            continue 'w_loop;
-                        }
-                        // check if i is left extend j
-                        if bounds[y_slice][i].0 <= bounds[y_slice][j].0
-                            && bounds[y_slice][i].1 >= bounds[y_slice][j].0
-                        // && bounds[y_slice][i].1 <= bounds[y_slice][j].1
-                        {
-                            // println!(
-                            //     "{:?} is left extend of {:?}",
-                            //     bounds[y_slice][i], bounds[y_slice][j]
-                            // );
-                            bounds[y_slice][j].0 = bounds[y_slice][i].0;
-                            bounds[y_slice].remove(i);
-                            // println!("bounds[{:?}] = {:?}", y_slice, bounds[y_slice]);
-                            continue 'w_loop;
-                        }
-                        // check if i is right extend j
-                        if bounds[y_slice][i].1 >= bounds[y_slice][j].1
-                            && bounds[y_slice][i].0 <= bounds[y_slice][j].1
-                        {
-                            // println!(
-                            //     "{:?} is right extend of {:?}",
-                            //     bounds[y_slice][i], bounds[y_slice][j]
-                            // );
-                            bounds[y_slice][j].1 = bounds[y_slice][i].1;
-                            bounds[y_slice].remove(i);
-                            // println!("bounds[{:?}] = {:?}", y_slice, bounds[y_slice]);
-                            continue 'w_loop;
-                        }
+        }
+        return None;
+    }
+
+    pub fn tuning_frequency((x, y): Point, multiplier: isize) -> isize {
+        x * multiplier + y
+    }
+
+    /// Draws `region` like the puzzle's illustration, `S` for sensors, `B`
+    /// for beacons, `#` where a sensor's coverage rules out a beacon and `.`
+    /// everywhere else.
+    pub fn render(&self, (xs, ys): Region) -> String {
+        let sensors = self.sensors.iter().map(|s| s.pos).collect::<HashSet<_>>();
+        let beacons = self
+            .sensors
+            .iter()
+            .map(|s| s.closest_beacon)
+            .collect::<HashSet<_>>();
+        let mut out = String::new();
+        for y in ys {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            let covered = self.covered_row(y);
+            for x in xs.clone() {
+                out.push(if sensors.contains(&(x, y)) {
+                    'S'
+                } else if beacons.contains(&(x, y)) {
+                    'B'
+                } else if covered.contains(x) {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+        }
+        return out;
+    }
+
+    /// `region` scaled down like [`Self::to_pgm`], shading each character by
+    /// how much of it is covered and marking the ones holding an uncovered
+    /// position with `X`, in red if `color` is set.
+    pub fn render_scaled(&self, region: Region, width: usize, height: usize, color: bool) -> String {
+        const SHADES: &[u8] = b" .:-=+*#";
+        let pixels = self.raster(region, width, height);
+        let mut out = String::new();
+        for ((_, col), &(covered, uncovered)) in pixels.iter() {
+            if col == 0 && !out.is_empty() {
+                out.push('\n');
+            }
+            match (uncovered, color) {
+                (true, true) => out.push_str("\x1b[1;31mX\x1b[0m"),
+                (true, false) => out.push('X'),
+                (false, _) => {
+                    let shade = covered as usize * (SHADES.len() - 1) / 255;
+                    out.push(SHADES[shade] as char)
+                }
+            }
+        }
+        return out;
+    }
+
+    /// `region` scaled down to at most `width` by `height` pixels, never more
+    /// than one per position. Each pixel holds the share of its positions a
+    /// sensor covers, out of 255 and sampled on its middle row, and whether
+    /// any of them is uncovered, checked on every row. Empty if the region or
+    /// the requested size is.
+    fn raster(&self, (xs, ys): Region, width: usize, height: usize) -> Grid<(u8, bool)> {
+        if xs.is_empty() || ys.is_empty() || width == 0 || height == 0 {
+            return Grid::new(0, 0, (0, false));
+        }
+        // first position of every pixel followed by one past the last
+        let edges = |range: RangeInclusive<isize>, pixels: usize| {
+            let len = range.end() - range.start() + 1;
+            let pixels = pixels.min(len as usize) as isize;
+            (0..=pixels)
+                .map(|i| range.start() + len * i / pixels)
+                .collect::<Vec<_>>()
+        };
+        let cols = edges(xs, width);
+        let rows = edges(ys, height);
+        let bounds = cols[0]..cols[cols.len() - 1];
+        let mut pixels = Grid::new(cols.len() - 1, rows.len() - 1, (0, false));
+        let mut covered = IntervalSet::new();
+
+        for (row, span) in rows.windows(2).enumerate() {
+            self.fill_row((span[0] + span[1] - 1) / 2, &mut covered);
+            for (col, cells) in cols.windows(2).enumerate() {
+                let inside = covered
+                    .intersection(&IntervalSet::from(cells[0]..cells[1]))
+                    .total_len();
+                pixels[(row, col)].0 = (inside * 255 / (cells[1] - cells[0])) as u8;
+            }
+            for y in span[0]..span[1] {
+                self.fill_row(y, &mut covered);
+                for gap in covered.complement_iter(bounds.clone()) {
+                    let first = cols.partition_point(|&x| x <= gap.start) - 1;
+                    let last = cols.partition_point(|&x| x < gap.end) - 1;
+                    for col in first..=last {
+                        pixels[(row, col)].1 = true;
                     }
                 }
-                break;
             }
         }
-        if bounds[y_slice].len() > 1 {
-            println!("Found: bounds[{:?}] = {:?}", y_slice, bounds[y_slice]);
-            return (y_slice as isize
-                + (std::cmp::min(bounds[y_slice][0].1, bounds[y_slice][1].1) + 1) * 4000000)
-                as usize;
+        return pixels;
+    }
+
+    /// Binary PGM image of how much of `region` the sensors cover, white
+    /// where they cover everything, see [`Self::raster`] for the scaling.
+    pub fn to_pgm(&self, region: Region, width: usize, height: usize) -> Vec<u8> {
+        let pixels = self.raster(region, width, height);
+        let mut out = format!("P5\n{} {}\n255\n", pixels.width(), pixels.height()).into_bytes();
+        out.extend(pixels.iter().map(|(_, &(covered, _))| covered));
+        return out;
+    }
+
+    /// Binary PPM image like [`Self::to_pgm`] with every pixel holding an
+    /// uncovered position in red.
+    pub fn to_ppm(&self, region: Region, width: usize, height: usize) -> Vec<u8> {
+        let pixels = self.raster(region, width, height);
+        let mut out = format!("P6\n{} {}\n255\n", pixels.width(), pixels.height()).into_bytes();
+        for (_, &(covered, uncovered)) in pixels.iter() {
+            match uncovered {
+                true => out.extend([255, 0, 0]),
+                false => out.extend([covered; 3]),
+            }
         }
+        return out;
     }
-    return 0;
 }
 
-pub fn part_1(input: &str) -> usize {
-    return find_slice(input, 2000000);
+/// Tuning frequency of the only position of `0..=max` square no sensor
+/// covers.
+fn distress_frequency(field: &SensorField, max: isize) -> Result<isize> {
+    let beacon = field
+        .find_uncovered((0..=max, 0..=max))
+        .with_context(|| format!("No uncovered position within 0..={}", max))?;
+    Ok(SensorField::tuning_frequency(beacon, MAX))
 }
-pub fn part_2(input: &str) -> usize {
-    return find_free(input, 4000000);
+
+/// Part 1 on row `row`, the real input uses `ROW`.
+pub fn part_1(input: &str, row: isize) -> Result<usize> {
+    Ok(Day15::parse(input)?.no_beacon_count(row))
+}
+
+/// Part 2 searching `0..=max`, the real input uses `MAX`.
+pub fn part_2(input: &str, max: isize) -> Result<isize> {
+    distress_frequency(&Day15::parse(input)?, max)
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Parsed = SensorField;
+    type Answer1 = usize;
+    type Answer2 = isize;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(input.parse()?)
+    }
+
+    fn part_1(field: &Self::Parsed) -> Result<Self::Answer1> {
+        Ok(field.no_beacon_count(ROW))
+    }
+
+    fn part_2(field: &Self::Parsed) -> Result<Self::Answer2> {
+        distress_frequency(field, MAX)
+    }
+
+    fn visualize(field: &Self::Parsed, color: bool) -> Result<Option<String>> {
+        let (width, height) = PICTURE;
+        Ok(Some(field.render_scaled(
+            (0..=MAX, 0..=MAX),
+            width,
+            height,
+            color,
+        )))
+    }
+
+    fn image(field: &Self::Parsed, format: ImageFormat) -> Result<Option<Vec<u8>>> {
+        let (width, height) = IMAGE;
+        Ok(Some(match format {
+            ImageFormat::Pgm => field.to_pgm((0..=MAX, 0..=MAX), width, height),
+            ImageFormat::Ppm => field.to_ppm((0..=MAX, 0..=MAX), width, height),
+        }))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-Sensor at x=9, y=16: closest beacon is at x=10, y=16
-Sensor at x=13, y=2: closest beacon is at x=15, y=3
-Sensor at x=12, y=14: closest beacon is at x=10, y=16
-Sensor at x=10, y=20: closest beacon is at x=10, y=16
-Sensor at x=14, y=17: closest beacon is at x=10, y=16
-Sensor at x=8, y=7: closest beacon is at x=2, y=10
-Sensor at x=2, y=0: closest beacon is at x=2, y=10
-Sensor at x=0, y=11: closest beacon is at x=2, y=10
-Sensor at x=20, y=14: closest beacon is at x=25, y=17
-Sensor at x=17, y=20: closest beacon is at x=21, y=22
-Sensor at x=16, y=7: closest beacon is at x=15, y=3
-Sensor at x=14, y=3: closest beacon is at x=15, y=3
-Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+    const INPUT: &str = include_str!("../../examples/day15/example1.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(find_slice(INPUT, 10), 26);
+        assert_eq!(part_1(INPUT, 10).unwrap(), 26);
     }
+
     #[test]
     fn test_part_2() {
-        assert_eq!(find_free(INPUT, 20), 56000011);
+        assert_eq!(part_2(INPUT, 20).unwrap(), 56000011);
+        assert!(part_2(INPUT, 13).is_err());
+        let field = Day15::parse(INPUT).unwrap();
+        let beacon = field.find_uncovered((0..=20, 0..=20)).unwrap();
+        assert_eq!(beacon, (14, 11));
+        assert_eq!(SensorField::tuning_frequency(beacon, 4000000), 56000011);
+        assert_eq!(field.find_uncovered((0..=13, 0..=20)), None);
+    }
+
+    #[test]
+    fn test_coverage_on_row() {
+        let field = Day15::parse(INPUT).unwrap();
+        assert_eq!(field.coverage_on_row(10), vec![-2..=24]);
+        assert_eq!(field.coverage_on_row(11), vec![-3..=13, 15..=25]);
+        assert_eq!(field.coverage_on_row(-100), vec![]);
+
+        // two sensors with a gap between them and no beacon on the row
+        let field = "Sensor at x=0, y=0: closest beacon is at x=0, y=2
+Sensor at x=10, y=0: closest beacon is at x=11, y=0"
+            .parse::<SensorField>()
+            .unwrap();
+        assert_eq!(field.coverage_on_row(1), vec![-1..=1, 10..=10]);
+        assert_eq!(field.coverage_on_row(0), vec![-2..=2, 9..=11]);
+        assert_eq!(field.no_beacon_count(0), 7);
+        assert_eq!(field.no_beacon_count(2), 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let field = Day15::parse(INPUT).unwrap();
+        let expected = "\
+...#########################...
+..####B######################..
+.###S#############.###########.";
+        assert_eq!(field.render((-4..=26, 9..=11)), expected);
+    }
+
+    #[test]
+    fn test_images() {
+        let field = Day15::parse(INPUT).unwrap();
+        let header = b"P6\n21 21\n255\n";
+        let ppm = field.to_ppm((0..=20, 0..=20), 100, 100);
+        assert_eq!(&ppm[..header.len()], header);
+        let pixels = ppm[header.len()..].chunks(3).collect::<Vec<_>>();
+        assert_eq!(pixels.len(), 21 * 21);
+        let red = (0..pixels.len())
+            .filter(|&i| pixels[i] == [255, 0, 0])
+            .collect::<Vec<_>>();
+        assert_eq!(red, vec![11 * 21 + 14]);
+
+        let pgm = field.to_pgm((0..=20, 0..=20), 7, 7);
+        let header = b"P5\n7 7\n255\n";
+        assert_eq!(&pgm[..header.len()], header);
+        assert_eq!(pgm.len(), header.len() + 49);
+        // the distress beacon at (14, 11) falls in pixel (row 3, column 4)
+        let ppm = field.to_ppm((0..=20, 0..=20), 7, 7);
+        assert_eq!(&ppm[ppm.len() - 49 * 3 + (3 * 7 + 4) * 3..][..3], [255, 0, 0]);
+
+        assert_eq!(field.to_pgm((RangeInclusive::new(5, 4), 0..=3), 10, 10), b"P5\n0 0\n255\n");
+        assert_eq!(field.to_ppm((0..=20, RangeInclusive::new(3, 0)), 10, 10), b"P6\n0 0\n255\n");
+        assert_eq!(field.to_pgm((0..=20, 0..=20), 0, 5), b"P5\n0 0\n255\n");
+    }
+
+    #[test]
+    fn test_render_scaled() {
+        let field = Day15::parse(INPUT).unwrap();
+        let picture = field.render_scaled((0..=20, 0..=20), 7, 7, false);
+        let rows = picture.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 7);
+        assert!(rows.iter().all(|row| row.len() == 7));
+        assert_eq!(picture.matches('X').count(), 1);
+        assert_eq!(rows[3].find('X'), Some(4));
+        assert!(field
+            .render_scaled((0..=20, 0..=20), 7, 7, true)
+            .contains("\x1b[1;31mX"));
     }
 }
@@ -1,256 +1,696 @@
-use anyhow::{Context, Result};
+use crate::solution::Solution;
+use crate::utill::parse_error::ParseError;
+use anyhow::{bail, Context, Result};
 use std::{
-    cell::RefCell,
-    collections::{BTreeSet, HashMap, HashSet},
-    fmt::Debug,
-    rc::Rc,
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    fmt::{Display, Write},
+    ops::Index,
+    str::FromStr,
 };
 
-struct Valve {
-    flow_rate: u32,
-    tunnels: Vec<Rc<RefCell<Valve>>>,
-    name: String,
+/// Handle to a [`Valve`] of a [`ValveGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ValveId(usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Valve {
+    pub name: String,
+    pub flow_rate: u32,
+    pub tunnels: Vec<ValveId>,
 }
 
-impl Debug for Valve {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(
-            f,
-            "Valve {} has flow rate = {}; tunnels lead to valves {}",
-            self.name,
-            self.flow_rate,
-            self.tunnels
-                .iter()
-                .map(|t| { t.borrow().name.clone() })
-                .collect::<Vec<_>>()
-                .join(",")
-        )
-    }
+/// The valve network, every valve stored once in the order of the input with
+/// tunnels as [`ValveId`]s into it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValveGraph {
+    valves: Vec<Valve>,
+    ids: HashMap<String, ValveId>,
 }
 
-fn parse_graph(input: &str) -> Result<HashMap<String, Rc<RefCell<Valve>>>> {
-    let mut map: HashMap<String, Rc<RefCell<Valve>>> = HashMap::new();
-
-    let mut verts = input
-        .lines()
-        .map(|ln| {
-            let name = ln.chars().skip(6).take(2).collect::<String>();
-            let (_, rate) = ln.split_once("rate=").context("Invalid Input string")?;
-            let (rate, _) = rate.split_once(";").context("Invalid Input string")?;
-            let rate = rate.parse::<u32>()?;
-            let (_, tunnels) = match ln.split_once("valves ") {
-                Some(v) => v,
-                None => ln
-                    .split_once("valve ")
-                    .context("No Tunnels found for Valve")?,
-            };
-            let tunnels = tunnels
-                .split(",")
-                .map(|t| {
-                    let name = t.trim().to_string();
-                    match map.get(&name) {
-                        Some(v) => {
-                            return v.clone();
-                        }
-                        None => {
-                            let val = Rc::new(RefCell::new(Valve {
-                                flow_rate: 0,
-                                tunnels: vec![],
-                                name: name.clone(),
-                            }));
-                            map.insert(name, val.clone());
-                            return val;
-                        }
-                    }
-                })
-                .collect::<Vec<_>>();
-            match map.get(&name) {
-                Some(v) => {
-                    v.borrow_mut().flow_rate = rate;
-                    v.borrow_mut().tunnels = tunnels;
-                    return Ok(v.clone());
+impl ValveGraph {
+    pub fn id(&self, name: &str) -> Option<ValveId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.valves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.valves.is_empty()
+    }
+
+    pub fn valves(&self) -> impl Iterator<Item = (ValveId, &Valve)> {
+        self.valves
+            .iter()
+            .enumerate()
+            .map(|(i, valve)| (ValveId(i), valve))
+    }
+
+    /// Valves passed through on a shortest walk from `from` to `to`, without
+    /// `from` itself.
+    pub fn path(&self, from: ValveId, to: ValveId) -> Option<Vec<ValveId>> {
+        let mut parents = vec![None; self.valves.len()];
+        let mut queue = VecDeque::from([from]);
+        while let Some(cur) = queue.pop_front() {
+            if cur == to {
+                let mut path = vec![];
+                let mut node = to;
+                while node != from {
+                    path.push(node);
+                    node = parents[node.0]?;
                 }
-                None => {
-                    let val = Rc::new(RefCell::new(Valve {
-                        flow_rate: rate,
-                        tunnels,
-                        name: name.clone(),
-                    }));
-                    map.insert(name, val.clone());
-                    return Ok(val);
+                path.reverse();
+                return Some(path);
+            }
+            for &next in self[cur].tunnels.iter() {
+                if next != from && parents[next.0].is_none() {
+                    parents[next.0] = Some(cur);
+                    queue.push_back(next);
                 }
             }
-        })
-        .collect::<Result<Vec<_>>>()?;
-    return Ok(map);
+        }
+        return None;
+    }
 }
-fn get_paths(
-    verts: &HashMap<String, Rc<RefCell<Valve>>>,
-    dp: &mut HashMap<(String, String, u32), u32>,
-    cur: String,
-    opened: &HashSet<String>,
-    all_paths: &mut HashMap<String, u32>,
-    min_left: u32,
-) -> u32 {
-    // check for dp
-    let mut opened_str = opened.iter().cloned().collect::<Vec<_>>();
-    opened_str.sort();
-    let opened_str = opened_str.join("");
-    let dp_key = (cur.clone(), opened_str, min_left);
-    if let Some(&v) = dp.get(&dp_key) {
-        return v;
+
+impl Index<ValveId> for ValveGraph {
+    type Output = Valve;
+
+    fn index(&self, id: ValveId) -> &Self::Output {
+        &self.valves[id.0]
     }
-    // base case
-    if min_left <= 0 {
-        return 0;
+}
+
+fn parse_line(ln: &str) -> Result<(&str, u32, Vec<&str>), ParseError> {
+    let name = ln
+        .strip_prefix("Valve ")
+        .and_then(|rest| rest.get(0..2))
+        .ok_or_else(|| ParseError::new(ln, ln, "Valve <name>"))?;
+    let (_, rate) = ln
+        .split_once("rate=")
+        .ok_or_else(|| ParseError::missing(ln, "flow rate=<rate>"))?;
+    let (rate, _) = rate
+        .split_once(';')
+        .ok_or_else(|| ParseError::missing(ln, ";"))?;
+    let rate = ParseError::parse::<u32>(ln, rate, "a flow rate")?;
+    let (_, tunnels) = match ln.split_once("valves ") {
+        Some(v) => v,
+        None => ln
+            .split_once("valve ")
+            .ok_or_else(|| ParseError::missing(ln, "tunnels lead to valves <names>"))?,
+    };
+    let tunnels = tunnels.split(',').map(|t| t.trim()).collect();
+    return Ok((name, rate, tunnels));
+}
+
+impl FromStr for ValveGraph {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s
+            .lines()
+            .map(|ln| parse_line(ln).map_err(|e| e.rebase(s, ln)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut graph = Self::default();
+        for &(name, flow_rate, _) in lines.iter() {
+            if graph.ids.contains_key(name) {
+                return Err(ParseError::new(s, name, "a valve that isn't defined yet"));
+            }
+            graph.ids.insert(name.to_string(), ValveId(graph.valves.len()));
+            graph.valves.push(Valve {
+                name: name.to_string(),
+                flow_rate,
+                tunnels: vec![],
+            });
+        }
+        for (i, (_, _, tunnels)) in lines.into_iter().enumerate() {
+            graph.valves[i].tunnels = tunnels
+                .into_iter()
+                .map(|name| {
+                    graph
+                        .id(name)
+                        .ok_or_else(|| ParseError::new(s, name, "a valve defined in the input"))
+                })
+                .collect::<Result<_, _>>()?;
+        }
+        return Ok(graph);
     }
+}
 
-    let mut best = 0;
-    let val = (min_left - 1) * verts.get(&cur).unwrap().borrow().flow_rate;
-    for adj in verts
-        .get(&cur)
-        .unwrap()
-        .borrow()
-        .tunnels
-        .iter()
-        .map(|adj| adj.borrow())
-    {
-        if val != 0 && !opened.contains(&cur) {
-            let mut cur_opened = opened.clone();
-            cur_opened.insert(cur.clone());
-            let new_val = val
-                + get_paths(
-                    verts,
-                    dp,
-                    adj.name.clone(),
-                    &cur_opened,
-                    all_paths,
-                    min_left - 2,
-                );
-            let mut cur_opened_str = cur_opened.iter().cloned().collect::<Vec<_>>();
-            cur_opened_str.sort();
-            let cur_opened_str = cur_opened_str.join("");
-            all_paths.insert(cur_opened_str, best + val);
-            best = std::cmp::max(best, new_val);
+/// Writes the graph back in the puzzle's format.
+impl Display for ValveGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, valve) in self.valves.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let tunnels = valve
+                .tunnels
+                .iter()
+                .map(|&id| self[id].name.as_str())
+                .collect::<Vec<_>>();
+            let lead = if tunnels.len() == 1 {
+                "tunnel leads to valve"
+            } else {
+                "tunnels lead to valves"
+            };
+            write!(
+                f,
+                "Valve {} has flow rate={}; {} {}",
+                valve.name,
+                valve.flow_rate,
+                lead,
+                tunnels.join(", ")
+            )?;
         }
-        best = std::cmp::max(
-            best,
-            get_paths(
-                verts,
-                dp,
-                adj.name.clone(),
-                &opened,
-                all_paths,
-                min_left - 1,
-            ),
-        );
+        Ok(())
     }
-    dp.insert(dp_key, best);
-    return best;
 }
 
-fn max_flow(
-    verts: &HashMap<String, Rc<RefCell<Valve>>>,
-    dp: &mut HashMap<(String, String, u32), u32>,
-    cur: String,
-    opened: &HashSet<String>,
-    min_left: u32,
-) -> u32 {
-    // check for dp
-    let mut opened_str = opened.iter().cloned().collect::<Vec<_>>();
-    opened_str.sort();
-    let opened_str = opened_str.join("");
-    let dp_key = (cur.clone(), opened_str, min_left);
-    if let Some(&v) = dp.get(&dp_key) {
-        return v;
+/// Distance between valves no tunnels connect, small enough that adding two
+/// of them doesn't overflow.
+const UNREACHABLE: u32 = u32::MAX / 2;
+
+/// The valves worth opening plus the start, interned to indices, with the
+/// minutes it takes to walk between any two of them. Valve `i` of the first
+/// `with_flow` is bit `1 << i` of an opened set.
+struct CompressedGraph {
+    ids: Vec<ValveId>,
+    flow: Vec<u32>,
+    dist: Vec<Vec<u32>>,
+    with_flow: usize,
+    start: usize,
+}
+
+impl CompressedGraph {
+    fn new(graph: &ValveGraph, start: &str) -> Result<Self> {
+        let mut all = graph.valves().map(|(id, _)| id).collect::<Vec<_>>();
+        // valves with a flow first so their index is also their bit
+        all.sort_by_key(|&id| (graph[id].flow_rate == 0, id));
+        let mut index = vec![0; all.len()];
+        for (i, id) in all.iter().enumerate() {
+            index[id.0] = i;
+        }
+        let with_flow = all
+            .iter()
+            .take_while(|&&id| graph[id].flow_rate > 0)
+            .count();
+        if with_flow > 64 {
+            bail!("Can't track more than 64 valves with a flow, found {}", with_flow);
+        }
+        let start = graph
+            .id(start)
+            .map(|id| index[id.0])
+            .with_context(|| format!("No valve {} to start from", start))?;
+
+        // Floyd–Warshall over every valve
+        let mut dist = vec![vec![UNREACHABLE; all.len()]; all.len()];
+        for (i, &id) in all.iter().enumerate() {
+            dist[i][i] = 0;
+            for adj in graph[id].tunnels.iter() {
+                dist[i][index[adj.0]] = 1;
+            }
+        }
+        for k in 0..all.len() {
+            for i in 0..all.len() {
+                for j in 0..all.len() {
+                    dist[i][j] = std::cmp::min(dist[i][j], dist[i][k] + dist[k][j]);
+                }
+            }
+        }
+
+        // keep the valves with a flow and the start
+        let mut keep = (0..with_flow).collect::<Vec<_>>();
+        if start >= with_flow {
+            keep.push(start);
+        }
+        Ok(Self {
+            ids: keep.iter().map(|&i| all[i]).collect(),
+            flow: keep.iter().map(|&i| graph[all[i]].flow_rate).collect(),
+            dist: keep
+                .iter()
+                .map(|&i| keep.iter().map(|&j| dist[i][j]).collect())
+                .collect(),
+            with_flow,
+            start: std::cmp::min(start, with_flow),
+        })
     }
-    // base case
-    if min_left <= 0 {
-        return 0;
+
+    /// Best pressure a single agent taking `travel_cost` minutes per tunnel
+    /// can release in `minutes` for every set of valves it could end up
+    /// opening, with the order to open them in.
+    fn best_per_opened_set(&self, minutes: u32, travel_cost: u32) -> HashMap<u64, BestRoute> {
+        let mut best = HashMap::new();
+        self.visit(minutes, travel_cost, 0, 0, &mut vec![], &mut best);
+        return best;
     }
 
-    let mut best = 0;
-    let val = (min_left - 1) * verts.get(&cur).unwrap().borrow().flow_rate;
-    for adj in verts
-        .get(&cur)
-        .unwrap()
-        .borrow()
-        .tunnels
-        .iter()
-        .map(|adj| adj.borrow())
-    {
-        if val != 0 && !opened.contains(&cur) {
-            let mut cur_opened = opened.clone();
-            cur_opened.insert(cur.clone());
-            best = std::cmp::max(
+    /// Tries every valve left to open after walking `route`, which starts
+    /// from the start valve with `minutes` left.
+    fn visit(
+        &self,
+        minutes: u32,
+        travel_cost: u32,
+        opened: u64,
+        pressure: u32,
+        route: &mut Vec<(usize, u32)>,
+        best: &mut HashMap<u64, BestRoute>,
+    ) {
+        let entry = best.entry(opened).or_default();
+        if pressure > entry.pressure {
+            *entry = BestRoute {
+                pressure,
+                route: route.clone(),
+            };
+        }
+        let (cur, min_left) = route.last().copied().unwrap_or((self.start, minutes));
+        for next in 0..self.with_flow {
+            if opened & (1 << next) != 0 || self.dist[cur][next] == UNREACHABLE {
+                continue;
+            }
+            // walk there and spend a minute opening it
+            let cost = self.dist[cur][next]
+                .saturating_mul(travel_cost)
+                .saturating_add(1);
+            if cost >= min_left {
+                continue;
+            }
+            let min_left = min_left - cost;
+            route.push((next, min_left));
+            self.visit(
+                minutes,
+                travel_cost,
+                opened | (1 << next),
+                pressure + min_left * self.flow[next],
+                route,
                 best,
-                val + max_flow(verts, dp, adj.name.clone(), &cur_opened, min_left - 2),
             );
+            route.pop();
+        }
+    }
+}
+
+/// The most pressure found for a set of opened valves and the order that
+/// releases it, as `(valve, minutes left once opened)`.
+#[derive(Clone, Debug, Default)]
+struct BestRoute {
+    pressure: u32,
+    route: Vec<(usize, u32)>,
+}
+
+/// One valve being opened, it releases pressure from the minute after.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Opening {
+    pub agent: usize,
+    pub valve: ValveId,
+    pub minute: u32,
+}
+
+/// The openings of the best plan found by [`ValveSolver::solve`], ordered by
+/// minute and then agent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    pub pressure: u32,
+    pub openings: Vec<Opening>,
+}
+
+/// Finds the most pressure a group of agents can release by opening valves
+/// within a time budget. Defaults to the puzzle's part 1: a single agent
+/// starting at `AA` with 30 minutes, a minute per tunnel.
+#[derive(Clone, Debug)]
+pub struct ValveSolver<'a> {
+    graph: &'a ValveGraph,
+    start: String,
+    minutes: u32,
+    travel_costs: Vec<u32>,
+}
+
+impl<'a> ValveSolver<'a> {
+    pub fn new(graph: &'a ValveGraph) -> Self {
+        Self {
+            graph,
+            start: "AA".to_string(),
+            minutes: 30,
+            travel_costs: vec![1],
+        }
+    }
+
+    /// Valve every agent starts at.
+    pub fn start(mut self, valve: &str) -> Self {
+        self.start = valve.to_string();
+        self
+    }
+
+    pub fn minutes(mut self, minutes: u32) -> Self {
+        self.minutes = minutes;
+        self
+    }
+
+    /// `count` agents each taking a minute per tunnel.
+    pub fn agents(mut self, count: usize) -> Self {
+        self.travel_costs = vec![1; count];
+        self
+    }
+
+    /// One agent per entry, taking that many minutes per tunnel.
+    pub fn travel_costs(mut self, minutes_per_tunnel: &[u32]) -> Self {
+        self.travel_costs = minutes_per_tunnel.to_vec();
+        self
+    }
+
+    pub fn solve(&self) -> Result<Schedule> {
+        if self.travel_costs.is_empty() {
+            bail!("Need at least one agent to open the valves");
+        }
+        let graph = CompressedGraph::new(self.graph, &self.start)?;
+
+        // valves opened by the agents so far, with the share of each of them
+        let mut combined = vec![(0, 0, vec![])];
+        let mut routes = vec![];
+        for (agent, &travel_cost) in self.travel_costs.iter().enumerate() {
+            let best = graph.best_per_opened_set(self.minutes, travel_cost);
+            let mut sets = best
+                .iter()
+                .map(|(&set, best)| (set, best.pressure))
+                .collect::<Vec<_>>();
+            routes.push(best);
+            sets.sort_by_key(|&(_, pressure)| Reverse(pressure));
+            if agent + 1 == self.travel_costs.len() {
+                combined = vec![best_disjoint(&combined, &sets)];
+                break;
+            }
+            let mut next: HashMap<u64, (u32, Vec<u64>)> = HashMap::new();
+            for (opened, pressure, shares) in combined.iter() {
+                for &(set, set_pressure) in sets.iter() {
+                    if opened & set != 0 {
+                        continue;
+                    }
+                    let pressure = pressure + set_pressure;
+                    let entry = next.entry(opened | set).or_default();
+                    if entry.1.is_empty() || pressure > entry.0 {
+                        let mut shares = shares.clone();
+                        shares.push(set);
+                        *entry = (pressure, shares);
+                    }
+                }
+            }
+            combined = next
+                .into_iter()
+                .map(|(opened, (pressure, shares))| (opened, pressure, shares))
+                .collect();
+            combined.sort_by_key(|&(_, pressure, _)| Reverse(pressure));
+        }
+        let (_, pressure, shares) = combined.remove(0);
+
+        let mut openings = vec![];
+        for (agent, (set, routes)) in shares.iter().zip(routes.iter()).enumerate() {
+            let route = &routes[set].route;
+            openings.extend(route.iter().map(|&(valve, min_left)| Opening {
+                agent,
+                valve: graph.ids[valve],
+                minute: self.minutes - min_left,
+            }));
+        }
+        openings.sort_by_key(|opening| (opening.minute, opening.agent));
+        return Ok(Schedule { pressure, openings });
+    }
+
+    /// Replays `schedule` minute by minute like the puzzle's walkthrough,
+    /// "== Minute 3 ==" followed by the open valves and what every agent does.
+    /// Walks that take several minutes per tunnel are told on arrival.
+    pub fn replay(&self, schedule: &Schedule) -> Result<String> {
+        let start = self
+            .graph
+            .id(&self.start)
+            .with_context(|| format!("No valve {} to start from", self.start))?;
+        // (minute, agent, what they did)
+        let mut actions = vec![];
+        for (agent, &travel_cost) in self.travel_costs.iter().enumerate() {
+            let (who, moves, opens) = match agent {
+                0 => ("You".to_string(), "move", "open"),
+                1 => ("The elephant".to_string(), "moves", "opens"),
+                n => (format!("Elephant {}", n), "moves", "opens"),
+            };
+            let mut pos = start;
+            let mut minute = 0;
+            for opening in schedule.openings.iter().filter(|o| o.agent == agent) {
+                let path = self.graph.path(pos, opening.valve).with_context(|| {
+                    format!("No way to valve {}", self.graph[opening.valve].name)
+                })?;
+                for valve in path {
+                    minute += travel_cost;
+                    let line = format!("{} {} to valve {}.", who, moves, self.graph[valve].name);
+                    actions.push((minute, agent, line));
+                }
+                minute += 1;
+                if minute != opening.minute {
+                    bail!(
+                        "{} can't open valve {} in minute {}",
+                        who,
+                        self.graph[opening.valve].name,
+                        opening.minute
+                    );
+                }
+                let line = format!("{} {} valve {}.", who, opens, self.graph[opening.valve].name);
+                actions.push((minute, agent, line));
+                pos = opening.valve;
+            }
+        }
+        actions.sort();
+
+        let mut out = String::new();
+        for minute in 1..=self.minutes {
+            if minute > 1 {
+                out.push('\n');
+            }
+            writeln!(out, "== Minute {} ==", minute)?;
+            let mut open = schedule
+                .openings
+                .iter()
+                .filter(|o| o.minute < minute)
+                .map(|o| &self.graph[o.valve])
+                .collect::<Vec<_>>();
+            open.sort_by(|a, b| a.name.cmp(&b.name));
+            let released = open.iter().map(|valve| valve.flow_rate).sum::<u32>();
+            let mut names = open.iter().map(|valve| valve.name.as_str()).collect::<Vec<_>>();
+            match names.len() {
+                0 => writeln!(out, "No valves are open.")?,
+                1 => writeln!(
+                    out,
+                    "Valve {} is open, releasing {} pressure.",
+                    names[0], released
+                )?,
+                2 => writeln!(
+                    out,
+                    "Valves {} and {} are open, releasing {} pressure.",
+                    names[0], names[1], released
+                )?,
+                _ => {
+                    let last = names.pop().unwrap_or_default();
+                    writeln!(
+                        out,
+                        "Valves {}, and {} are open, releasing {} pressure.",
+                        names.join(", "),
+                        last,
+                        released
+                    )?
+                }
+            }
+            for (_, _, line) in actions.iter().filter(|(m, _, _)| *m == minute) {
+                writeln!(out, "{}", line)?;
+            }
+        }
+        return Ok(out);
+    }
+}
+
+/// Best pair of an already combined set and one of `sets` that share no
+/// valves, both lists sorted by most pressure first.
+fn best_disjoint(combined: &[(u64, u32, Vec<u64>)], sets: &[(u64, u32)]) -> (u64, u32, Vec<u64>) {
+    let mut best = (0, 0, vec![]);
+    for (opened, pressure, shares) in combined.iter() {
+        for &(set, set_pressure) in sets.iter() {
+            if pressure + set_pressure <= best.1 && !best.2.is_empty() {
+                break;
+            }
+            if opened & set == 0 {
+                let mut shares = shares.clone();
+                shares.push(set);
+                best = (opened | set, pressure + set_pressure, shares);
+            }
         }
-        best = std::cmp::max(
-            best,
-            max_flow(verts, dp, adj.name.clone(), &opened, min_left - 1),
-        );
     }
-    dp.insert(dp_key, best);
     return best;
 }
 
-pub fn part_1(input: &str) -> u32 {
-    let verts = parse_graph(input).expect("Error Parsing graph");
-    return max_flow(
-        &verts,
-        &mut HashMap::new(),
-        "AA".to_string(),
-        &HashSet::new(),
-        30,
-    );
+pub fn part_1(input: &str) -> Result<u32> {
+    Day16::part_1(&Day16::parse(input)?)
 }
-pub fn part_2(input: &str) -> u32 {
-    let verts = parse_graph(input).expect("Error Parsing graph");
-    let mut paths = HashMap::new();
-    get_paths(
-        &verts,
-        &mut HashMap::new(),
-        "AA".to_string(),
-        &HashSet::new(),
-        &mut paths,
-        26,
-    );
-    println!("BB: {:?}", paths.get("BB"));
-    0
-    // let mut best = 0;
-    // for (opened, old_best) in all_opened.iter() {
-    //     let comp = max_flow(&verts, &mut HashMap::new(), "AA".to_string(), opened, 26);
-    //     println!("old best: {old_best:?} for {opened:?}\ncomp: {comp}");
-    //     best = std::cmp::max(best, old_best + comp);
-    //     if best <= 1707 {
-    //         panic!("Too big!")
-    //     }
-    // }
-    // best
+
+pub fn part_2(input: &str) -> Result<u32> {
+    Day16::part_2(&Day16::parse(input)?)
+}
+
+/// You and the elephant after teaching it for 4 minutes.
+fn with_elephant(graph: &ValveGraph) -> ValveSolver<'_> {
+    ValveSolver::new(graph).minutes(26).agents(2)
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Parsed = ValveGraph;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(input.parse()?)
+    }
+
+    fn part_1(graph: &Self::Parsed) -> Result<Self::Answer1> {
+        Ok(ValveSolver::new(graph).solve()?.pressure)
+    }
+
+    fn part_2(graph: &Self::Parsed) -> Result<Self::Answer2> {
+        Ok(with_elephant(graph).solve()?.pressure)
+    }
+
+    /// The minute by minute walkthrough of part 2's schedule.
+    fn visualize(graph: &Self::Parsed, _color: bool) -> Result<Option<String>> {
+        let solver = with_elephant(graph);
+        return Ok(Some(solver.replay(&solver.solve()?)?));
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-Valve BB has flow rate=13; tunnels lead to valves CC, AA
-Valve CC has flow rate=2; tunnels lead to valves DD, BB
-Valve EE has flow rate=3; tunnels lead to valves FF, DD
-Valve FF has flow rate=0; tunnels lead to valves EE, GG
-Valve GG has flow rate=0; tunnels lead to valves FF, HH
-Valve HH has flow rate=22; tunnel leads to valve GG
-Valve II has flow rate=0; tunnels lead to valves AA, JJ
-Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-Valve JJ has flow rate=21; tunnel leads to valve II";
+    const INPUT: &str = include_str!("../../examples/day16/example1.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT), 1651);
+        assert_eq!(part_1(INPUT).unwrap(), 1651);
     }
+
+    #[test]
+    fn test_round_trip() {
+        let graph = INPUT.parse::<ValveGraph>().unwrap();
+        assert_eq!(graph.to_string(), INPUT.trim_end());
+        assert_eq!(graph[graph.id("JJ").unwrap()].flow_rate, 21);
+        let err = "Valve AA has flow rate=0; tunnel leads to valve BB"
+            .parse::<ValveGraph>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, columns 49-50: expected a valve defined in the input"
+        );
+    }
+
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(INPUT), 1707);
+        assert_eq!(part_2(INPUT).unwrap(), 1707);
+    }
+
+    #[test]
+    fn test_solver() {
+        let graph = INPUT.parse::<ValveGraph>().unwrap();
+        let schedule = ValveSolver::new(&graph).minutes(26).agents(2).solve().unwrap();
+        assert_eq!(schedule.pressure, 1707);
+        let released = schedule
+            .openings
+            .iter()
+            .map(|o| (26 - o.minute) * graph[o.valve].flow_rate)
+            .sum::<u32>();
+        assert_eq!(released, 1707);
+        let first = schedule.openings[0];
+        assert_eq!(first.minute, 2);
+        assert_eq!(graph[first.valve].name, "DD");
+
+        let slow = ValveSolver::new(&graph).travel_costs(&[2]).solve().unwrap();
+        assert!(slow.pressure < 1651);
+        assert!(ValveSolver::new(&graph).start("ZZ").solve().is_err());
+        assert!(ValveSolver::new(&graph).agents(0).solve().is_err());
+    }
+
+    #[test]
+    fn test_visualize() {
+        let graph = Day16::parse(INPUT).unwrap();
+        let picture = Day16::visualize(&graph, false).unwrap().unwrap();
+        assert!(picture.starts_with("== Minute 1 ==\nNo valves are open.\n"));
+        assert!(picture.contains("The elephant opens valve JJ.\n"));
+        assert!(picture.ends_with(
+            "== Minute 26 ==\nValves BB, CC, DD, EE, HH, and JJ are open, releasing 81 pressure.\n"
+        ));
+    }
+
+    #[test]
+    fn test_unreachable_valve() {
+        let graph = "Valve AA has flow rate=0; tunnels lead to valves BB
+Valve BB has flow rate=10; tunnels lead to valves AA
+Valve CC has flow rate=20; tunnels lead to valves DD
+Valve DD has flow rate=0; tunnels lead to valves CC"
+            .parse::<ValveGraph>()
+            .unwrap();
+        // AA to BB takes 3 minutes and opening it 1, leaving 26
+        let slow = ValveSolver::new(&graph).travel_costs(&[3]).solve().unwrap();
+        assert_eq!(slow.pressure, 260);
+        assert_eq!(slow.openings.len(), 1);
+        let fast = ValveSolver::new(&graph).solve().unwrap();
+        assert_eq!(fast.pressure, 280);
+    }
+
+    #[test]
+    fn test_replay() {
+        let graph = INPUT.parse::<ValveGraph>().unwrap();
+        let solver = ValveSolver::new(&graph);
+        let replay = solver.replay(&solver.solve().unwrap()).unwrap();
+        let expected = "\
+== Minute 1 ==
+No valves are open.
+You move to valve DD.
+
+== Minute 2 ==
+No valves are open.
+You open valve DD.
+
+== Minute 3 ==
+Valve DD is open, releasing 20 pressure.
+You move to valve CC.
+";
+        assert!(replay.starts_with(expected), "{}", replay);
+        assert!(replay.contains(
+            "== Minute 6 ==\nValves BB and DD are open, releasing 33 pressure.\nYou move to valve AA.\n"
+        ));
+        assert!(replay.ends_with(
+            "== Minute 30 ==\nValves BB, CC, DD, EE, HH, and JJ are open, releasing 81 pressure.\n"
+        ));
+
+        let solver = solver.minutes(26).agents(2);
+        let schedule = solver.solve().unwrap();
+        assert_eq!(schedule.pressure, 1707);
+        let replay = solver.replay(&schedule).unwrap();
+        assert!(replay.contains(
+            "
+== Minute 2 ==
+No valves are open.
+You open valve DD.
+The elephant moves to valve JJ.
+"
+        ));
+        assert!(replay.contains(
+            "
+== Minute 11 ==
+Valves BB, CC, DD, HH, and JJ are open, releasing 78 pressure.
+You open valve EE.
+"
+        ));
+        assert!(replay.ends_with(
+            "
+== Minute 26 ==
+Valves BB, CC, DD, EE, HH, and JJ are open, releasing 81 pressure.
+"
+        ));
     }
 }
@@ -1,25 +1,26 @@
+use crate::solution::Solution;
+use crate::utill::parse_error::ParseError;
+use anyhow::Result;
+
 trait Game {
     fn game(&self, op: &RPS) -> u32;
 }
 
 #[derive(Clone)]
-enum RPS {
+pub enum RPS {
     ROCK = 1,
     PAPER = 2,
     SCISSORS = 3,
 }
 
 impl std::str::FromStr for RPS {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "A" => Ok(Self::ROCK),
             "B" => Ok(Self::PAPER),
             "C" => Ok(Self::SCISSORS),
-            "X" => Ok(Self::ROCK),
-            "Y" => Ok(Self::PAPER),
-            "Z" => Ok(Self::SCISSORS),
-            _ => Err(()),
+            _ => Err(ParseError::new(s, s, "a move A, B or C")),
         }
     }
 }
@@ -48,14 +49,42 @@ enum Outcome {
     DRAW = 3,
 }
 
-impl std::str::FromStr for Outcome {
-    type Err = ();
+/// Second column of the guide, a move in part 1 and an outcome in part 2.
+#[derive(Clone, Copy)]
+pub enum Response {
+    X,
+    Y,
+    Z,
+}
+
+impl std::str::FromStr for Response {
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "X" => Ok(Self::LOSE),
-            "Y" => Ok(Self::DRAW),
-            "Z" => Ok(Self::WIN),
-            _ => Err(()),
+            "X" => Ok(Self::X),
+            "Y" => Ok(Self::Y),
+            "Z" => Ok(Self::Z),
+            _ => Err(ParseError::new(s, s, "a response X, Y or Z")),
+        }
+    }
+}
+
+impl From<Response> for RPS {
+    fn from(response: Response) -> Self {
+        match response {
+            Response::X => RPS::ROCK,
+            Response::Y => RPS::PAPER,
+            Response::Z => RPS::SCISSORS,
+        }
+    }
+}
+
+impl From<Response> for Outcome {
+    fn from(response: Response) -> Self {
+        match response {
+            Response::X => Outcome::LOSE,
+            Response::Y => Outcome::DRAW,
+            Response::Z => Outcome::WIN,
         }
     }
 }
@@ -77,37 +106,75 @@ impl Game for Outcome {
     }
 }
 
-fn do_day2<T>(input: &str) -> u32
+pub type Round = (RPS, Response);
+
+fn parse_round(game: &str) -> Result<Round, ParseError> {
+    let (op, response) = game
+        .split_once(' ')
+        .ok_or_else(|| ParseError::missing(game, "a space between the moves"))?;
+    let op = op.parse::<RPS>().map_err(|e| e.rebase(game, op))?;
+    let response = response
+        .parse::<Response>()
+        .map_err(|e| e.rebase(game, response))?;
+    Ok((op, response))
+}
+
+fn do_day2<T>(rounds: &[Round]) -> u32
 where
-    T: Game + std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
+    T: Game + From<Response>,
 {
-    input
-        .lines()
-        .map(|game| {
-            let (op, outcome) = game.split_once(" ").unwrap();
-            let op = op.parse::<RPS>().unwrap();
-            let outcome = outcome.parse::<T>().unwrap();
-            outcome.game(&op)
-        })
+    rounds
+        .iter()
+        .map(|(op, response)| T::from(*response).game(op))
         .sum()
 }
 
-pub fn part_1(input: &str) -> u32 {
-    do_day2::<RPS>(input)
+pub fn part_1(input: &str) -> Result<u32> {
+    Day2::part_1(&Day2::parse(input)?)
 }
 
-pub fn part_2(input: &str) -> u32 {
-    do_day2::<Outcome>(input)
+pub fn part_2(input: &str) -> Result<u32> {
+    Day2::part_2(&Day2::parse(input)?)
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Parsed = Vec<Round>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(input
+            .lines()
+            .map(|game| parse_round(game).map_err(|e| e.rebase(input, game)))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part_1(rounds: &Self::Parsed) -> Result<Self::Answer1> {
+        Ok(do_day2::<RPS>(rounds))
+    }
+
+    fn part_2(rounds: &Self::Parsed) -> Result<Self::Answer2> {
+        Ok(do_day2::<Outcome>(rounds))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "A Y\nB X\nC Z";
+    const INPUT: &str = include_str!("../../examples/day2/example1.txt");
     #[test]
     fn test() {
-        assert_eq!(part_1(INPUT), 15);
-        assert_eq!(part_2(INPUT), 12);
+        assert_eq!(part_1(INPUT).unwrap(), 15);
+        assert_eq!(part_2(INPUT).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_bad_move() {
+        let err = part_1("A Y\nB Q").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.columns(), 2..3);
     }
 }
@@ -1,65 +1,89 @@
+use crate::solution::Solution;
 use crate::utill::chunk::ChunkIterator;
+use crate::utill::parse_error::ParseError;
+use anyhow::{Context, Result};
 use std::collections::HashSet;
 
 fn calculate(item: u8) -> u32 {
-    return (if item as u8 > 'Z' as u8 {
-        item - 'a' as u8 + 1
+    return (if item > b'Z' {
+        item - b'a' + 1
     } else {
-        item - 'A' as u8 + 27
+        item - b'A' + 27
     }) as u32;
 }
-pub fn part_1(input: &str) -> u32 {
-    return input
+
+/// The items of every bag, making sure each can be scored by `calculate`.
+fn bags(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    input
         .lines()
-        .map(|bag| {
-            let (first, second) = bag.split_at(bag.len() / 2);
-            let first_map: HashSet<u8> = first.as_bytes().iter().cloned().collect();
-            let second_map: HashSet<u8> = second.as_bytes().iter().cloned().collect();
-            return second_map
-                .iter()
-                .filter_map(|&item| {
-                    if !first_map.contains(&item) {
-                        return None;
-                    }
-                    return Some(calculate(item));
-                })
-                .sum::<u32>();
+        .map(|bag| match bag.find(|c: char| !c.is_ascii_alphabetic()) {
+            Some(i) => Err(ParseError::new(input, &bag[i..i + 1], "an item a-z or A-Z")),
+            None => Ok(bag.as_bytes().to_vec()),
         })
-        .sum::<u32>();
+        .collect()
 }
 
-pub fn part_2(input: &str) -> u32 {
-    input
-        .lines()
-        .chunk()
-        .filter_map(|[first, second, third]| {
-            let first: HashSet<u8> = first.as_bytes().iter().cloned().collect();
-            let second: HashSet<u8> = second.as_bytes().iter().cloned().collect();
-            let third: HashSet<u8> = third.as_bytes().iter().cloned().collect();
-            return Some(
-                first
+pub fn part_1(input: &str) -> Result<u32> {
+    Day3::part_1(&Day3::parse(input)?)
+}
+
+pub fn part_2(input: &str) -> Result<u32> {
+    Day3::part_2(&Day3::parse(input)?)
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Parsed = Vec<Vec<u8>>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(bags(input)?)
+    }
+
+    fn part_1(bags: &Self::Parsed) -> Result<Self::Answer1> {
+        return Ok(bags
+            .iter()
+            .map(|bag| {
+                let (first, second) = bag.split_at(bag.len() / 2);
+                let first_map: HashSet<u8> = first.iter().cloned().collect();
+                let second_map: HashSet<u8> = second.iter().cloned().collect();
+                return second_map
+                    .iter()
+                    .filter(|item| first_map.contains(item))
+                    .map(|&item| calculate(item))
+                    .sum::<u32>();
+            })
+            .sum::<u32>());
+    }
+
+    fn part_2(bags: &Self::Parsed) -> Result<Self::Answer2> {
+        bags.iter()
+            .chunk_exact()
+            .map(|group| {
+                let [first, second, third] = group.context("Expected groups of 3 elves")?;
+                let first: HashSet<u8> = first.iter().cloned().collect();
+                let second: HashSet<u8> = second.iter().cloned().collect();
+                let third: HashSet<u8> = third.iter().cloned().collect();
+                return Ok(first
                     .iter()
                     .filter(|&v| second.contains(v) && third.contains(v))
                     .map(|&item| calculate(item))
-                    .sum::<u32>(),
-            );
-        })
-        .sum::<u32>()
+                    .sum::<u32>());
+            })
+            .sum()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
+    const INPUT: &str = include_str!("../../examples/day3/example1.txt");
 
     #[test]
     fn test() {
-        assert_eq!(part_1(INPUT), 157);
-        assert_eq!(part_2(INPUT), 70);
+        assert_eq!(part_1(INPUT).unwrap(), 157);
+        assert_eq!(part_2(INPUT).unwrap(), 70);
     }
 }
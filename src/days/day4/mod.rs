@@ -1,50 +1,76 @@
-use std::collections::HashSet;
-pub fn part_1(input: &str) -> usize {
-    input
-        .lines()
-        .filter(|elves| {
-            let (elf1, elf2) = elves.split_once(",").unwrap();
-            let elf1: Vec<u32> = elf1.split("-").flat_map(str::parse::<u32>).collect();
-            let elf2: Vec<u32> = elf2.split("-").flat_map(str::parse::<u32>).collect();
-            if elf1[0] <= elf2[0] && elf1[1] >= elf2[1] || elf2[0] <= elf1[0] && elf2[1] >= elf1[1]
-            {
-                return true;
-            }
-            false
-        })
-        .count()
+use crate::solution::Solution;
+use crate::utill::{intervals::IntervalSet, parse_error::ParseError};
+use anyhow::Result;
+
+fn parse_range(input: &str, range: &str) -> Result<IntervalSet<u32>, ParseError> {
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| ParseError::new(input, range, "a range <start>-<end>"))?;
+    let start = ParseError::parse(input, start, "a section number")?;
+    let end: u32 = ParseError::parse(input, end, "a section number")?;
+    return Ok(IntervalSet::from(start..end + 1));
 }
 
-pub fn part_2(input: &str) -> usize {
+type Pair = (IntervalSet<u32>, IntervalSet<u32>);
+
+fn parse_elves(input: &str) -> Result<Vec<Pair>, ParseError> {
     input
         .lines()
-        .filter(|elves| {
-            let (elf1, elf2) = elves.split_once(",").unwrap();
-            let mut elf1: Vec<u32> = elf1.split("-").flat_map(str::parse::<u32>).collect();
-            let mut elf2: Vec<u32> = elf2.split("-").flat_map(str::parse::<u32>).collect();
-            if elf2[0] < elf1[0] {
-                (elf1, elf2) = (elf2, elf1);
-            }
-            if elf2[0] <= elf1[1] {
-                return true;
-            }
-            return false;
+        .map(|elves| {
+            let (elf1, elf2) = elves
+                .split_once(',')
+                .ok_or_else(|| ParseError::new(input, elves, "two ranges separated by ,"))?;
+            Ok((parse_range(input, elf1)?, parse_range(input, elf2)?))
         })
-        .count()
+        .collect()
 }
+
+pub fn part_1(input: &str) -> Result<usize> {
+    Day4::part_1(&Day4::parse(input)?)
+}
+
+pub fn part_2(input: &str) -> Result<usize> {
+    Day4::part_2(&Day4::parse(input)?)
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Parsed = Vec<Pair>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(parse_elves(input)?)
+    }
+
+    fn part_1(pairs: &Self::Parsed) -> Result<Self::Answer1> {
+        Ok(pairs
+            .iter()
+            .filter(|(elf1, elf2)| {
+                // one range contains the other if it is all they have in common
+                let common = elf1.intersection(elf2);
+                common == *elf1 || common == *elf2
+            })
+            .count())
+    }
+
+    fn part_2(pairs: &Self::Parsed) -> Result<Self::Answer2> {
+        Ok(pairs
+            .iter()
+            .filter(|(elf1, elf2)| !elf1.intersection(elf2).is_empty())
+            .count())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8";
+    const INPUT: &str = include_str!("../../examples/day4/example1.txt");
 
     #[test]
     fn test() {
-        assert_eq!(part_1(INPUT), 2);
-        assert_eq!(part_2(INPUT), 4);
+        assert_eq!(part_1(INPUT).unwrap(), 2);
+        assert_eq!(part_2(INPUT).unwrap(), 4);
     }
 }
@@ -1,32 +1,58 @@
+use crate::solution::Solution;
+use crate::utill::parse_error::ParseError;
+use anyhow::{bail, Context, Result};
 use std::collections::VecDeque;
 
-fn parse_instruction(instruction: &str) -> Vec<usize> {
-    instruction
-        .split(" ")
+/// Moves `count` crates from stack `from` to stack `to`, both 0 based.
+#[derive(Clone, Copy, Debug)]
+struct Move {
+    count: usize,
+    from: usize,
+    to: usize,
+    /// Line of the input the move was read from, for errors.
+    line: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Crates {
+    stacks: Vec<VecDeque<char>>,
+    moves: Vec<Move>,
+}
+
+fn parse_instruction(
+    input: &str,
+    instruction: &str,
+    stacks: usize,
+) -> Result<[usize; 3], ParseError> {
+    let nums = instruction
+        .split(' ')
         .flat_map(str::parse::<usize>)
-        .map(|i| i - 1)
-        .collect::<Vec<_>>()
+        .collect::<Vec<_>>();
+    match nums[..] {
+        [count, from, to] if (1..=stacks).contains(&from) && (1..=stacks).contains(&to) => {
+            Ok([count, from - 1, to - 1])
+        }
+        _ => Err(ParseError::new(
+            input,
+            instruction,
+            "move <count> from <stack> to <stack>",
+        )),
+    }
 }
 
-fn build_original(original: &str) -> Vec<VecDeque<char>> {
-    let len = original
-        .lines()
-        .rev()
-        .next()
-        .unwrap()
-        .trim_end()
+fn build_original(input: &str, original: &str) -> Result<Vec<VecDeque<char>>, ParseError> {
+    let numbers = original.lines().next_back().unwrap_or_default().trim_end();
+    let len = numbers
         .chars()
-        .rev()
-        .next()
-        .unwrap()
-        .to_digit(10)
-        .unwrap();
+        .next_back()
+        .and_then(|c| c.to_digit(10))
+        .ok_or_else(|| ParseError::new(input, numbers, "a row of stack numbers"))?;
 
     let mut v: Vec<VecDeque<char>> = vec![VecDeque::new(); len as usize];
     for crates in original.lines() {
         let mut counter = 0;
-        for (i, cr) in crates.split(" ").enumerate() {
-            if cr.as_bytes().len() != 3 {
+        for (i, cr) in crates.split(' ').enumerate() {
+            if cr.len() != 3 {
                 if i % 4 == 0 {
                     counter += 1
                 }
@@ -36,63 +62,132 @@ fn build_original(original: &str) -> Vec<VecDeque<char>> {
             counter += 1;
         }
     }
-    return v;
+    return Ok(v);
 }
 
-pub fn part_1(input: &str) -> String {
-    let (original, rest) = input.split_once("\n\n").unwrap();
-    let mut v = build_original(original);
-    for instruction in rest.lines() {
-        let instruction = parse_instruction(instruction);
-        for _ in 0..=instruction[0] {
-            let val = v[instruction[1]].pop_front().unwrap();
-            v[instruction[2]].push_front(val);
-        }
+impl std::str::FromStr for Crates {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        let (original, rest) = input.split_once("\n\n").ok_or_else(|| {
+            ParseError::missing(input, "a blank line between crates and instructions")
+        })?;
+        let stacks = build_original(input, original)?;
+        let offset = original.lines().count() + 2;
+        let moves = rest
+            .lines()
+            .enumerate()
+            .map(|(i, instruction)| {
+                let [count, from, to] = parse_instruction(input, instruction, stacks.len())?;
+                Ok(Move {
+                    count,
+                    from,
+                    to,
+                    line: i + offset,
+                })
+            })
+            .collect::<Result<_, ParseError>>()?;
+        Ok(Self { stacks, moves })
+    }
+}
+
+/// Runs every move through `apply`, pointing at the line of the first one
+/// that fails, then reads off the top crate of each stack.
+fn rearrange<F>(crates: &Crates, mut apply: F) -> Result<String>
+where
+    F: FnMut(&mut [VecDeque<char>], Move) -> Result<()>,
+{
+    let mut v = crates.stacks.clone();
+    for &mv in crates.moves.iter() {
+        apply(&mut v, mv).with_context(|| {
+            format!(
+                "Can't apply line {}: move {} from {} to {}",
+                mv.line,
+                mv.count,
+                mv.from + 1,
+                mv.to + 1
+            )
+        })?;
     }
     v.iter_mut()
-        .map(|a| a.pop_front().unwrap())
-        .fold("".to_string(), |acc, c| format!("{}{}", acc, c))
+        .enumerate()
+        .map(|(i, a)| a.pop_front().context(format!("Stack {} is empty", i + 1)))
+        .collect()
 }
 
-pub fn part_2(input: &str) -> String {
-    let (original, rest) = input.split_once("\n\n").unwrap();
-    let mut v = build_original(original);
-    for instruction in rest.lines() {
-        let instruction = parse_instruction(instruction);
-        for val in (0..=instruction[0])
-            .flat_map(|_| v[instruction[1]].pop_front())
-            .collect::<Vec<_>>()
-            .iter()
-            .rev()
-        {
-            v[instruction[2]].push_front(*val);
-        }
+pub fn part_1(input: &str) -> Result<String> {
+    Day5::part_1(&Day5::parse(input)?)
+}
+
+pub fn part_2(input: &str) -> Result<String> {
+    Day5::part_2(&Day5::parse(input)?)
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Parsed = Crates;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(input.parse()?)
     }
 
-    v.iter_mut()
-        .map(|a| a.pop_front().unwrap())
-        .fold("".to_string(), |acc, c| format!("{}{}", acc, c))
+    fn part_1(crates: &Self::Parsed) -> Result<Self::Answer1> {
+        rearrange(crates, |v, Move { count, from, to, .. }| {
+            for _ in 0..count {
+                let val = v[from]
+                    .pop_front()
+                    .context(format!("Stack {} is empty", from + 1))?;
+                v[to].push_front(val);
+            }
+            Ok(())
+        })
+    }
+
+    fn part_2(crates: &Self::Parsed) -> Result<Self::Answer2> {
+        rearrange(crates, |v, Move { count, from, to, .. }| {
+            if v[from].len() < count {
+                bail!("Stack {} only has {} crates", from + 1, v[from].len());
+            }
+            let moved = v[from].drain(0..count).collect::<Vec<_>>();
+            for val in moved.into_iter().rev() {
+                v[to].push_front(val);
+            }
+            Ok(())
+        })
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "    [D]
-[N] [C]
-[Z] [M] [P]
- 1   2   3
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
+    const INPUT: &str = include_str!("../../examples/day5/example1.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT), "CMZ");
+        assert_eq!(part_1(INPUT).unwrap(), "CMZ");
     }
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(INPUT), "MCD");
+        assert_eq!(part_2(INPUT).unwrap(), "MCD");
+    }
+    #[test]
+    fn test_bad_input() {
+        let err = INPUT.replace("\n\n", "\n").parse::<Crates>().unwrap_err();
+        assert_eq!(err.expected(), "a blank line between crates and instructions");
+
+        let err = INPUT.replace("   3\n", "   x\n").parse::<Crates>().unwrap_err();
+        assert_eq!((err.line(), err.columns()), (4, 0..10));
+
+        let err = INPUT
+            .replace("from 1 to 2", "from 1 to 9")
+            .parse::<Crates>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 9, columns 1-18: expected move <count> from <stack> to <stack>"
+        );
     }
 }
@@ -1,48 +1,117 @@
+use crate::solution::{Part, Solution, Variant};
+use crate::utill::chunk::ChunkIterator;
+use crate::utill::parse_error::ParseError;
+use anyhow::{Context, Result};
 use std::collections::HashSet;
 
-fn find_unique_b3nny_style(input: &str, amount: usize) -> Option<usize> {
-    input
-        .as_bytes()
-        .windows(amount)
-        .position(|arr| arr.iter().copied().collect::<HashSet<u8>>().len() == amount)
-        .map(|n| n + amount)
+fn find_unique_b3nny_style<const N: usize>(stream: &[u8]) -> Option<usize> {
+    stream
+        .iter()
+        .copied()
+        .windows::<N>()
+        .position(|arr| arr.iter().copied().collect::<HashSet<u8>>().len() == N)
+        .map(|n| n + N)
 }
 
-fn find_unique_mine(input: &str, amount: usize) -> Option<usize> {
-    for i in 0..(input.len() - amount) {
+fn find_unique_mine(stream: &[u8], amount: usize) -> Option<usize> {
+    for i in 0..stream.len().saturating_sub(amount) {
         let end = i + amount;
-        if input[i..end].chars().collect::<HashSet<char>>().len() == amount {
+        if stream[i..end].iter().collect::<HashSet<_>>().len() == amount {
             return Some(end);
         }
     }
     return None;
 }
 
-pub fn part_1(input: &str) -> usize {
-    find_unique_b3nny_style(input, 4).unwrap()
+/// The datastream on the first line, which must only hold letters a-z.
+fn datastream(input: &str) -> Result<Vec<u8>, ParseError> {
+    let stream = input.lines().next().unwrap_or_default();
+    match stream.find(|c: char| !c.is_ascii_lowercase()) {
+        Some(i) => Err(ParseError::new(input, &stream[i..i + 1], "a letter a-z")),
+        None => Ok(stream.as_bytes().to_vec()),
+    }
+}
+
+pub fn part_1(input: &str) -> Result<usize> {
+    Day6::part_1(&Day6::parse(input)?)
 }
 
-pub fn part_2(input: &str) -> usize {
-    find_unique_mine(input, 14).unwrap()
+pub fn part_2(input: &str) -> Result<usize> {
+    Day6::part_2(&Day6::parse(input)?)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Parsed = Vec<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(datastream(input)?)
+    }
+
+    fn part_1(stream: &Self::Parsed) -> Result<Self::Answer1> {
+        find_unique_b3nny_style::<4>(stream).context("No start-of-packet marker found")
+    }
+
+    fn part_2(stream: &Self::Parsed) -> Result<Self::Answer2> {
+        find_unique_mine(stream, 14).context("No start-of-message marker found")
+    }
+
+    fn variants() -> Vec<Variant<Self::Parsed>> {
+        vec![
+            Variant {
+                name: "find_unique_mine",
+                part: Part::One,
+                run: |stream| {
+                    let marker =
+                        find_unique_mine(stream, 4).context("No start-of-packet marker found")?;
+                    Ok(marker.to_string())
+                },
+            },
+            Variant {
+                name: "find_unique_b3nny_style",
+                part: Part::Two,
+                run: |stream| {
+                    let marker = find_unique_b3nny_style::<14>(stream)
+                        .context("No start-of-message marker found")?;
+                    Ok(marker.to_string())
+                },
+            },
+        ]
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    const INPUTS: [&str; 5] = [
+        include_str!("../../examples/day6/example1.txt"),
+        include_str!("../../examples/day6/example2.txt"),
+        include_str!("../../examples/day6/example3.txt"),
+        include_str!("../../examples/day6/example4.txt"),
+        include_str!("../../examples/day6/example5.txt"),
+    ];
+
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1("bvwbjplbgvbhsrlpgdmjqwftvncz"), 5);
-        assert_eq!(part_1("nppdvjthqldpwncqszvftbrmjlhg"), 6);
-        assert_eq!(part_1("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), 10);
-        assert_eq!(part_1("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), 11);
+        let markers = INPUTS.map(|input| part_1(input).unwrap());
+        assert_eq!(markers, [7, 5, 6, 10, 11]);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), 19);
-        assert_eq!(part_2("bvwbjplbgvbhsrlpgdmjqwftvncz"), 23);
-        assert_eq!(part_2("nppdvjthqldpwncqszvftbrmjlhg"), 23);
-        assert_eq!(part_2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), 29);
-        assert_eq!(part_2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), 26);
+        let markers = INPUTS.map(|input| part_2(input).unwrap());
+        assert_eq!(markers, [19, 23, 23, 29, 26]);
+    }
+
+    #[test]
+    fn test_variants() {
+        for input in INPUTS {
+            let stream = Day6::parse(input).unwrap();
+            assert_eq!(find_unique_mine(&stream, 4), part_1(input).ok());
+            assert_eq!(find_unique_b3nny_style::<14>(&stream), part_2(input).ok());
+        }
     }
 }
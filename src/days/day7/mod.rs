@@ -1,3 +1,5 @@
+use crate::solution::Solution;
+use crate::utill::parse_error::ParseError;
 use anyhow::{bail, Context, Result};
 use std::{
     cell::{Cell, RefCell},
@@ -8,13 +10,13 @@ use std::{
 };
 
 #[derive(Debug, Default)]
-struct File {
+pub struct File {
     name: String,
     size: u64,
 }
 
 #[derive(Default)]
-struct Dir {
+pub struct Dir {
     name: String,
     contents: HashMap<String, FileType>,
     parent: Option<Rc<RefCell<Dir>>>,
@@ -55,7 +57,7 @@ impl FromIterator<Command> for Result<Rc<RefCell<Dir>>> {
                         .borrow()
                         .contents
                         .get(&folder_name)
-                        .context("Folder should exist")?
+                        .context(format!("Folder {} should exist", folder_name))?
                     {
                         FileType::Dir(d) => d.clone(),
                         FileType::File(_) => {
@@ -84,7 +86,7 @@ impl FromIterator<Command> for Result<Rc<RefCell<Dir>>> {
     }
 }
 
-struct DirIter<'a> {
+pub struct DirIter<'a> {
     iter_stack: Vec<Iter<'a, String, FileType>>,
 }
 
@@ -94,7 +96,7 @@ impl<'a> Iterator for DirIter<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             let val = self.iter_stack.last_mut()?.next();
-            if let None = val {
+            if val.is_none() {
                 self.iter_stack.pop();
             } else {
                 return val;
@@ -108,7 +110,9 @@ impl Dir {
         return input
             .split("$ ")
             .filter(|s| !s.is_empty())
-            .flat_map(str::parse::<Command>)
+            .map(|cmd| cmd.parse::<Command>().map_err(|e| e.rebase(input, cmd)))
+            .collect::<Result<Vec<_>, ParseError>>()?
+            .into_iter()
             .collect::<Result<Rc<RefCell<Self>>>>();
     }
 
@@ -119,7 +123,7 @@ impl Dir {
     }
 
     pub fn get_size(&self) -> u64 {
-        if let None = self.size.get() {
+        if self.size.get().is_none() {
             self.size
                 .set(Some(self.contents.iter().fold(0, |acc, (_, c)| {
                     acc + match c {
@@ -139,17 +143,17 @@ impl Debug for Dir {
 }
 
 #[derive(Debug)]
-enum FileType {
+pub enum FileType {
     Dir(Rc<RefCell<Dir>>),
     File(File),
 }
 
 impl FromStr for FileType {
-    type Err = anyhow::Error;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (first, second) = s
-            .split_once(" ")
-            .context("Invalid cmd format for parsing")?;
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new(s, s, "dir <name> or <size> <name>"))?;
         match first {
             "dir" => Ok(FileType::Dir(Rc::new(RefCell::new(Dir {
                 name: second.to_string(),
@@ -157,7 +161,7 @@ impl FromStr for FileType {
                 ..Dir::default()
             })))),
             _ => {
-                let size = first.parse::<u64>()?;
+                let size = ParseError::parse::<u64>(s, first, "dir or a file size")?;
                 return Ok(FileType::File(File {
                     name: second.to_string(),
                     size,
@@ -174,24 +178,24 @@ enum Command {
 }
 
 impl FromStr for Command {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (cmd, rest) = s
-            .split_once("\n")
-            .context("Invalid cmd format for parsing")?;
-        match &cmd[0..2] {
-            "cd" => Ok(Self::CD(cmd[3..].to_string())),
-            "ls" => {
+        let (cmd, rest) = s.split_once('\n').unwrap_or((s, ""));
+        match cmd.get(0..2) {
+            Some("cd") => Ok(Self::CD(
+                cmd.strip_prefix("cd ")
+                    .ok_or_else(|| ParseError::missing(cmd, "a folder"))?
+                    .to_string(),
+            )),
+            Some("ls") => {
                 let files = rest
                     .lines()
-                    .map(str::parse::<FileType>)
-                    .collect::<Result<Vec<_>>>()?;
+                    .map(|ln| ln.parse::<FileType>().map_err(|e| e.rebase(s, ln)))
+                    .collect::<Result<Vec<_>, _>>()?;
                 return Ok(Self::LS(files));
             }
-            _ => {
-                bail!("");
-            }
+            _ => Err(ParseError::new(s, cmd, "cd or ls")),
         }
     }
 }
@@ -226,64 +230,63 @@ fn recurssive_min(root: &Rc<RefCell<Dir>>, min: &mut u64, free_space: &u64) {
     }
 }
 
-pub fn part_1(input: &str) -> u64 {
-    let fs = Dir::build_fs(input).unwrap();
-
-    let mut sum = 0u64;
-    recurssive_sum(&fs, &mut sum);
+pub fn part_1(input: &str) -> Result<u64> {
+    Day7::part_1(&Day7::parse(input)?)
+}
 
-    return sum;
+pub fn part_2(input: &str) -> Result<u64> {
+    Day7::part_2(&Day7::parse(input)?)
 }
 
-// pub fn part_1_f(input: &str) -> u64 {
-//     let fs = Dir::build_fs(input).unwrap();
-//     fs.borrow().iter().filter_map(|(_, ft)| match ft {
-//         FileType::Dir(d) => {},
-//         FileType::File(_) => None,
-//     })
-// }
-pub fn part_2(input: &str) -> u64 {
-    let fs = Dir::build_fs(input).unwrap();
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Parsed = Rc<RefCell<Dir>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Dir::build_fs(input)
+    }
 
-    let mut min = u64::MAX;
-    let cur_min = fs.borrow().get_size();
-    let free_space = 70000000 - cur_min;
-    recurssive_min(&fs, &mut min, &free_space);
-    return min;
+    fn part_1(fs: &Self::Parsed) -> Result<Self::Answer1> {
+        let mut sum = 0u64;
+        recurssive_sum(fs, &mut sum);
+
+        return Ok(sum);
+    }
+
+    fn part_2(fs: &Self::Parsed) -> Result<Self::Answer2> {
+        let mut min = u64::MAX;
+        let cur_min = fs.borrow().get_size();
+        let free_space = 70000000u64
+            .checked_sub(cur_min)
+            .context("Filesystem is larger than the disk")?;
+        recurssive_min(fs, &mut min, &free_space);
+        return Ok(min);
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k";
+    const INPUT: &str = include_str!("../../examples/day7/example1.txt");
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT), 95437);
+        assert_eq!(part_1(INPUT).unwrap(), 95437);
     }
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(INPUT), 24933642);
+        assert_eq!(part_2(INPUT).unwrap(), 24933642);
+    }
+    #[test]
+    fn test_bad_input() {
+        let err = part_1("$ cd /\n$ ls\n12x a.txt\n$ rm a.txt").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, columns 1-3: expected dir or a file size"
+        );
+        let err = part_1("$ cd /\n$ ls\n1 a.txt\n$ rm a.txt").unwrap_err();
+        assert_eq!(err.to_string(), "line 4, columns 3-10: expected cd or ls");
     }
 }
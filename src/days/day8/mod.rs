@@ -1,133 +1,85 @@
-#[derive(Clone, Copy, Debug)]
-struct Vec2D(isize, isize);
+use crate::solution::Solution;
+use crate::utill::grid::{Grid, Pos};
+use crate::utill::parse_error::ParseError;
+use crate::utill::v2d::Vec2D;
+use anyhow::Result;
 
-struct GridIterator<'a> {
-    grid: &'a Vec<Vec<u32>>,
-    cords: Vec2D,
-    direction: Vec2D,
+fn is_visible(grid: &Grid<u32>, pos: Pos, direction: Vec2D) -> bool {
+    let inital_val = grid[pos];
+    return grid.ray(pos, direction).all(|(_, &val)| val < inital_val);
 }
 
-fn make_directional_grid_iter<'a>(
-    grid: &'a Vec<Vec<u32>>,
-    cords: Vec2D,
-    direction: Vec2D,
-) -> GridIterator<'a> {
-    GridIterator {
-        grid,
-        cords,
-        direction,
-    }
-}
-
-impl<'a> Iterator for GridIterator<'a> {
-    type Item = (&'a u32, Vec2D);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self
-            .grid
-            .get(self.cords.0 as usize)
-            .map(|r| r.get(self.cords.1 as usize))
-        {
-            Some(Some(v)) => {
-                let val = Some((v, self.cords));
-                self.cords = self.cords + self.direction;
-                val
-            }
-            _ => None,
+fn check_score(grid: &Grid<u32>, pos: Pos, direction: Vec2D) -> u32 {
+    let inital_val = grid[pos];
+    let mut count = 0;
+    for (_, &val) in grid.ray(pos, direction) {
+        count += 1;
+        if val >= inital_val {
+            break;
         }
     }
+    return count;
 }
 
-impl std::ops::Add for Vec2D {
-    type Output = Vec2D;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1)
-    }
+fn parse_grid(input: &str) -> Result<Grid<u32>, ParseError> {
+    return Grid::parse_with(input, "a tree height 0-9", |c| c.to_digit(10));
 }
 
-fn check_direction(grid: &Vec<Vec<u32>>, direction: Vec2D, start: Vec2D) -> bool {
-    let inital_val = grid[start.0 as usize][start.1 as usize];
-    for (&val, _) in make_directional_grid_iter(grid, start + direction, direction) {
-        if val >= inital_val {
-            return false;
-        }
-    }
-    return true;
+pub fn part_1(input: &str) -> Result<usize> {
+    Day8::part_1(&parse_grid(input)?)
 }
-fn check_score(grid: &Vec<Vec<u32>>, direction: Vec2D, start: Vec2D) -> u32 {
-    let inital_val = grid[start.0 as usize][start.1 as usize];
-    let mut count = 0;
-    for (&val, _) in make_directional_grid_iter(grid, start + direction, direction) {
-        if val >= inital_val {
-            return count + 1;
-        }
-        count += 1;
-    }
-    return count;
+
+pub fn part_2(input: &str) -> Result<u32> {
+    Day8::part_2(&parse_grid(input)?)
 }
 
-pub fn part_1(input: &str) -> usize {
-    let grid: Vec<_> = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .filter_map(|c| c.to_digit(10))
-                .collect::<Vec<_>>()
-        })
-        .collect();
+pub struct Day8;
 
-    let mut counter = 0;
-    for i in 1..(grid.len() - 1) {
-        for j in 1..(grid[i].len() - 1) {
-            if check_direction(&grid, Vec2D(0, 1), Vec2D(i as isize, j as isize))
-                || check_direction(&grid, Vec2D(1, 0), Vec2D(i as isize, j as isize))
-                || check_direction(&grid, Vec2D(0, -1), Vec2D(i as isize, j as isize))
-                || check_direction(&grid, Vec2D(-1, 0), Vec2D(i as isize, j as isize))
-            {
-                counter += 1
-            }
-        }
+impl Solution for Day8 {
+    type Parsed = Grid<u32>;
+    type Answer1 = usize;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(parse_grid(input)?)
     }
-    return counter + (grid.len() * 4) - 4;
-}
-pub fn part_2(input: &str) -> u32 {
-    let grid: Vec<_> = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .filter_map(|c| c.to_digit(10))
-                .collect::<Vec<_>>()
-        })
-        .collect();
-    let mut best_score = 0;
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            let score = check_score(&grid, Vec2D(0, 1), Vec2D(i as isize, j as isize))
-                * check_score(&grid, Vec2D(1, 0), Vec2D(i as isize, j as isize))
-                * check_score(&grid, Vec2D(0, -1), Vec2D(i as isize, j as isize))
-                * check_score(&grid, Vec2D(-1, 0), Vec2D(i as isize, j as isize));
-            best_score = std::cmp::max(score, best_score);
-        }
+
+    fn part_1(grid: &Self::Parsed) -> Result<Self::Answer1> {
+        return Ok(grid
+            .positions()
+            .filter(|&pos| {
+                Vec2D::DIRECTIONS_4
+                    .iter()
+                    .any(|&direction| is_visible(grid, pos, direction))
+            })
+            .count());
+    }
+
+    fn part_2(grid: &Self::Parsed) -> Result<Self::Answer2> {
+        return Ok(grid
+            .positions()
+            .map(|pos| {
+                Vec2D::DIRECTIONS_4
+                    .iter()
+                    .map(|&direction| check_score(grid, pos, direction))
+                    .product::<u32>()
+            })
+            .max()
+            .unwrap_or_default());
     }
-    return best_score;
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT: &'static str = "30373
-25512
-65332
-33549
-35390";
+    const INPUT: &str = include_str!("../../examples/day8/example1.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT), 21);
+        assert_eq!(part_1(INPUT).unwrap(), 21);
     }
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(INPUT), 8);
+        assert_eq!(part_2(INPUT).unwrap(), 8);
     }
 }
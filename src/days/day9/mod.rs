@@ -1,26 +1,11 @@
-use anyhow::{bail, Context, Result};
-use std::{collections::HashSet, str::FromStr};
-
-#[derive(Default, Clone, Copy, Eq, Hash, PartialEq, Debug)]
-struct Vec2D(isize, isize);
-impl std::ops::Add for Vec2D {
-    type Output = Vec2D;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-impl std::ops::Sub for Vec2D {
-    type Output = Vec2D;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self(self.0 - rhs.0, self.1 - rhs.1)
-    }
-}
+use crate::solution::Solution;
+use crate::utill::parse_error::ParseError;
+use anyhow::{Context, Result};
+use crate::utill::v2d::Vec2D;
+use std::collections::HashSet;
 
 fn _draw_board(knots: &[Vec2D], min: Vec2D, max: Vec2D) {
-    for y in (min.1..=max.1).rev() {
+    for y in min.1..=max.1 {
         for x in min.0..max.0 {
             let mut c = ".".to_string();
             if x == 0 && y == 0 {
@@ -38,61 +23,37 @@ fn _draw_board(knots: &[Vec2D], min: Vec2D, max: Vec2D) {
             }
             print!("{}", c);
         }
-        println!("");
-    }
-}
-
-impl Vec2D {
-    fn make_one(&self) -> Self {
-        let mut n = *self;
-        n.0 = self.0 / std::cmp::max(1, self.0.abs());
-        n.1 = self.1 / std::cmp::max(1, self.1.abs());
-        return n;
-    }
-
-    fn is_one_away(&self, rhs: Self) -> bool {
-        let s = *self - rhs;
-        return std::cmp::max(s.0.abs(), s.1.abs()) <= 1;
+        println!();
     }
 }
 
-impl FromStr for Vec2D {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "U" => Ok(Vec2D(0, 1)),
-            "D" => Ok(Vec2D(0, -1)),
-            "L" => Ok(Vec2D(-1, 0)),
-            "R" => Ok(Vec2D(1, 0)),
-            _ => {
-                bail!("Invalid Direction")
-            }
-        }
-    }
+/// Moves of the head, one step in a direction repeated a number of times.
+fn parse_moves(input: &str) -> Result<Vec<(Vec2D, isize)>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (dir, count) = line
+                .split_once(' ')
+                .ok_or_else(|| ParseError::new(input, line, "<direction> <steps>"))?;
+            let dir = dir.parse::<Vec2D>().map_err(|e| e.rebase(input, dir))?;
+            let count = ParseError::parse::<isize>(input, count, "a number of steps")?;
+            Ok((dir, count))
+        })
+        .collect()
 }
 
-fn follow_knots<const N: usize>(input: &str) -> Result<usize> {
+fn follow_knots<const N: usize>(moves: &[(Vec2D, isize)]) -> Result<usize> {
     let mut knots = [Vec2D(0, 0); N];
     let mut visited: HashSet<Vec2D> = HashSet::from([knots[0]]);
 
-    for val in input.lines().map(|line| {
-        line.split_once(" ")
-            .context("Invalid Line")
-            .map(|(dir, count)| -> Result<(Vec2D, isize)> {
-                let dir = dir.parse::<Vec2D>()?;
-                let count = count.parse::<isize>()?;
-                return Ok((dir, count));
-            })
-    }) {
-        let (dir, count) = val??;
+    for &(dir, count) in moves {
         for _ in 0..count {
-            knots[0] = knots[0] + dir;
+            knots[0] += dir;
             for i in 1..N {
-                if knots[i - 1].is_one_away(knots[i]) {
+                if knots[i - 1].chebyshev(knots[i]) <= 1 {
                     continue;
                 }
-                knots[i] = knots[i] + (knots[i - 1] - knots[i]).make_one();
+                knots[i] += (knots[i - 1] - knots[i]).signum();
             }
             visited.insert(*knots.last().context("knots doesn't have a last value")?);
         }
@@ -101,40 +62,46 @@ fn follow_knots<const N: usize>(input: &str) -> Result<usize> {
     return Ok(visited.len());
 }
 
-pub fn part_1(input: &str) -> usize {
-    follow_knots::<2>(input).unwrap()
+pub fn part_1(input: &str) -> Result<usize> {
+    Day9::part_1(&Day9::parse(input)?)
 }
 
-pub fn part_2(input: &str) -> usize {
-    follow_knots::<10>(input).unwrap()
+pub fn part_2(input: &str) -> Result<usize> {
+    Day9::part_2(&Day9::parse(input)?)
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Parsed = Vec<(Vec2D, isize)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(parse_moves(input)?)
+    }
+
+    fn part_1(moves: &Self::Parsed) -> Result<Self::Answer1> {
+        follow_knots::<2>(moves)
+    }
+
+    fn part_2(moves: &Self::Parsed) -> Result<Self::Answer2> {
+        follow_knots::<10>(moves)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    const INPUT1: &'static str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2";
-    const INPUT2: &'static str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20";
+    const INPUT1: &str = include_str!("../../examples/day9/example1.txt");
+    const INPUT2: &str = include_str!("../../examples/day9/example2.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(INPUT1), 13);
+        assert_eq!(part_1(INPUT1).unwrap(), 13);
     }
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(INPUT2), 36);
+        assert_eq!(part_2(INPUT2).unwrap(), 36);
     }
 }
@@ -1,16 +1,36 @@
-pub mod day1;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
+use crate::solution::{Day, Runner};
+
+/// Declares every day module and builds the registry the `aoc` runner uses.
+/// Adding a day only needs a new entry here.
+macro_rules! days {
+    ($($day:literal => $module:ident::$solution:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        pub fn registry() -> Vec<Box<dyn Runner>> {
+            vec![$(Box::new(Day::<$module::$solution>::new($day))),*]
+        }
+    };
+}
+
+days! {
+    1 => day1::Day1,
+    2 => day2::Day2,
+    3 => day3::Day3,
+    4 => day4::Day4,
+    5 => day5::Day5,
+    6 => day6::Day6,
+    7 => day7::Day7,
+    8 => day8::Day8,
+    9 => day9::Day9,
+    10 => day10::Day10,
+    11 => day11::Day11,
+    12 => day12::Day12,
+    13 => day13::Day13,
+    14 => day14::Day14,
+    15 => day15::Day15,
+    16 => day16::Day16,
+}
+
+pub fn get(day: u8) -> Option<Box<dyn Runner>> {
+    registry().into_iter().find(|r| r.day() == day)
+}
@@ -0,0 +1,201 @@
+use crate::answers::Answers;
+use crate::solution::{Part, Runner};
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// What [`extract`] found in a puzzle description.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Puzzle {
+    /// Every code block, in order. The example input is usually the first.
+    pub blocks: Vec<String>,
+    /// The last emphasised code of each part, which is the example's answer.
+    pub answers: Answers,
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn strip_tags(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Every `open ... close` in `s`, with the offset it starts at.
+fn between<'a>(s: &'a str, open: &'a str, close: &'a str) -> Vec<(usize, &'a str)> {
+    let mut found = vec![];
+    let mut from = 0;
+    while let Some(start) = s[from..].find(open).map(|i| from + i + open.len()) {
+        let Some(end) = s[start..].find(close).map(|i| start + i) else {
+            break;
+        };
+        found.push((start, &s[start..end]));
+        from = end + close.len();
+    }
+    found
+}
+
+/// Last emphasised code in `s`, HTML `<code><em>..</em></code>` or Markdown
+/// `` **`..`** ``.
+fn last_answer(s: &str) -> Option<String> {
+    [
+        ("<code><em>", "</em></code>"),
+        ("<em><code>", "</code></em>"),
+        ("**`", "`**"),
+        ("*`", "`*"),
+    ]
+    .into_iter()
+    .flat_map(|(open, close)| between(s, open, close))
+    .max_by_key(|(start, _)| *start)
+    .map(|(_, answer)| decode_entities(&strip_tags(answer)))
+}
+
+/// Pulls the example blocks and answers out of a saved puzzle description,
+/// either the HTML of the puzzle page or a Markdown conversion of it.
+pub fn extract(description: &str) -> Puzzle {
+    let mut blocks = between(description, "<pre><code>", "</code></pre>")
+        .into_iter()
+        .map(|(start, block)| (start, decode_entities(&strip_tags(block))))
+        .collect::<Vec<_>>();
+    for (start, block) in between(description, "```", "```") {
+        // skip the language tag
+        let block = block.split_once('\n').map_or("", |(_, block)| block);
+        blocks.push((start, block.to_string()));
+    }
+    blocks.sort_by_key(|(start, _)| *start);
+
+    let (part_1, part_2) = match description.find("--- Part Two ---") {
+        Some(i) => description.split_at(i),
+        None => (description, ""),
+    };
+    Puzzle {
+        blocks: blocks.into_iter().map(|(_, block)| block).collect(),
+        answers: Answers {
+            part_1: last_answer(part_1),
+            part_2: last_answer(part_2),
+        },
+    }
+}
+
+/// `examples/dayN`, holding `exampleK.txt` inputs each with the answers to it
+/// in `exampleK.toml`.
+pub fn dir(day: u8) -> String {
+    format!("examples/day{}", day)
+}
+
+/// Every example in `dir` with its answers, sorted by name.
+pub fn load(dir: &Path) -> Result<Vec<(String, String, Answers)>> {
+    let mut examples = vec![];
+    for entry in std::fs::read_dir(dir).context(format!("Error reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|e| e != "txt") {
+            continue;
+        }
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let input = std::fs::read_to_string(&path)?;
+        let toml = std::fs::read_to_string(path.with_extension("toml"))
+            .context(format!("No answers for {}", path.display()))?;
+        let answers = Answers::from_toml(&toml).context(path.display().to_string())?;
+        examples.push((name.to_string(), input, answers));
+    }
+    examples.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(examples)
+}
+
+/// Runs a day against every example in `dir`, failing on the first answer
+/// that doesn't match.
+pub fn check(runner: &dyn Runner, dir: &Path) -> Result<()> {
+    for (name, input, answers) in load(dir)? {
+        let parts = [Part::One, Part::Two]
+            .into_iter()
+            .filter(|&part| answers.get(part).is_some())
+            .collect::<Vec<_>>();
+        for (part, answer) in runner.run(&input, &parts).context(name.clone())? {
+            let answer = answer.context(format!("{} part {}", name, part))?;
+            let expected = answers.get(part).unwrap_or_default();
+            if answer != expected {
+                bail!(
+                    "day {} {} part {}: expected {} but got {}",
+                    runner.day(),
+                    name,
+                    part,
+                    expected,
+                    answer
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::days;
+
+    const HTML: &str = "<article class=\"day-desc\"><h2>--- Day 1: Calorie Counting ---</h2>
+<p>For example:</p>
+<pre><code>1000
+2000
+
+3000
+</code></pre>
+<p>This elf is carrying <code><em>3000</em></code> calories, that's <em>the most</em>.</p>
+</article>
+<article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2>
+<pre><code>a &lt;<em>b</em>&gt; &amp; c</code></pre>
+<p>In total <em><code>6000</code></em>, not <code>45</code>.</p>
+</article>";
+
+    const MARKDOWN: &str = "## --- Day 6: Tuning Trouble ---
+```
+mjqjpqmgbljsphdztnvjfqwrcgsmlb
+```
+The first marker is after character **`7`**.
+## --- Part Two ---
+Now it's after character **`19`**.";
+
+    #[test]
+    fn test_extract_html() {
+        let puzzle = extract(HTML);
+        assert_eq!(puzzle.blocks, vec!["1000\n2000\n\n3000\n", "a <b> & c"]);
+        assert_eq!(puzzle.answers.part_1.as_deref(), Some("3000"));
+        assert_eq!(puzzle.answers.part_2.as_deref(), Some("6000"));
+    }
+
+    #[test]
+    fn test_extract_markdown() {
+        let puzzle = extract(MARKDOWN);
+        assert_eq!(puzzle.blocks, vec!["mjqjpqmgbljsphdztnvjfqwrcgsmlb\n"]);
+        assert_eq!(puzzle.answers.part_1.as_deref(), Some("7"));
+        assert_eq!(puzzle.answers.part_2.as_deref(), Some("19"));
+    }
+
+    /// Every day with an `examples/dayN` directory is checked against it.
+    #[test]
+    fn test_examples() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut checked = 0;
+        for runner in days::registry() {
+            let dir = root.join(dir(runner.day()));
+            if dir.is_dir() {
+                check(runner.as_ref(), &dir).unwrap();
+                checked += 1;
+            }
+        }
+        assert!(checked > 0);
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::{Context, Result};
+use std::io::Read;
+
+/// Where a day's puzzle input is read from.
+///
+/// By default that's `inputs/dayN.txt`, the directory can be moved with the
+/// `AOC_INPUT_DIR` environment variable and a profile picks the input set of
+/// one person, `inputs/<profile>/dayN.txt`. An explicit file overrides all of
+/// that, `-` reading from stdin.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputSource {
+    pub file: Option<String>,
+    pub dir: Option<String>,
+    pub profile: Option<String>,
+}
+
+impl InputSource {
+    pub fn from_env() -> Self {
+        Self {
+            dir: std::env::var("AOC_INPUT_DIR").ok(),
+            ..Self::default()
+        }
+    }
+
+    /// Path the input of `day` is read from, `-` for stdin.
+    pub fn path(&self, day: u8) -> String {
+        if let Some(file) = &self.file {
+            return file.clone();
+        }
+        let dir = self.dir.as_deref().unwrap_or("inputs");
+        match &self.profile {
+            Some(profile) => format!("{}/{}/day{}.txt", dir, profile, day),
+            None => format!("{}/day{}.txt", dir, day),
+        }
+    }
+
+    pub fn read(&self, day: u8) -> Result<String> {
+        let path = self.path(day);
+        if path == "-" {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .context("Error reading stdin")?;
+            return Ok(input);
+        }
+        std::fs::read_to_string(&path).context(format!("Error reading file {}", path))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let mut source = InputSource::default();
+        assert_eq!(source.path(7), "inputs/day7.txt");
+        source.dir = Some("/tmp/aoc".to_string());
+        assert_eq!(source.path(7), "/tmp/aoc/day7.txt");
+        source.profile = Some("alice".to_string());
+        assert_eq!(source.path(7), "/tmp/aoc/alice/day7.txt");
+        source.file = Some("-".to_string());
+        assert_eq!(source.path(7), "-");
+    }
+}
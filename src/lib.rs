@@ -1,2 +1,9 @@
+#![allow(clippy::needless_return, clippy::upper_case_acronyms)]
+
+pub mod answers;
+pub mod bench;
 pub mod days;
+pub mod examples;
+pub mod input;
+pub mod solution;
 pub mod utill;
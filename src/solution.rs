@@ -0,0 +1,163 @@
+use crate::bench::{measure, BenchOptions, Stats};
+use anyhow::{bail, Context, Result};
+use std::fmt::Display;
+
+/// A single day of the advent calendar.
+///
+/// `parse` turns the raw puzzle input into whatever model the day works on and
+/// both parts are run against that parsed value. Every step is fallible so a
+/// malformed input is reported instead of aborting the process.
+pub trait Solution {
+    type Parsed;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Parsed>;
+    fn part_1(parsed: &Self::Parsed) -> Result<Self::Answer1>;
+    fn part_2(parsed: &Self::Parsed) -> Result<Self::Answer2>;
+
+    /// Alternate implementations of the parts, benchmarked against the main
+    /// ones by `aoc bench`.
+    fn variants() -> Vec<Variant<Self::Parsed>> {
+        vec![]
+    }
+
+    /// A picture of the solved puzzle for `aoc --visualize`, with ANSI colours
+    /// if `color` is set. `None` if the day doesn't draw anything.
+    fn visualize(_parsed: &Self::Parsed, _color: bool) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// A binary image of the solved puzzle for `aoc --image`. `None` if the
+    /// day doesn't draw one.
+    fn image(_parsed: &Self::Parsed, _format: ImageFormat) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+/// Another way of solving one part, run on the same parsed input.
+pub struct Variant<P> {
+    pub name: &'static str,
+    pub part: Part,
+    pub run: fn(&P) -> Result<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// Netpbm formats a day can draw itself in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Greyscale `.pgm`.
+    Pgm,
+    /// Colour `.ppm`.
+    Ppm,
+}
+
+impl ImageFormat {
+    /// The format matching the extension of `path`, if any.
+    pub fn from_path(path: &str) -> Option<Self> {
+        match std::path::Path::new(path).extension()?.to_str()? {
+            "pgm" => Some(Self::Pgm),
+            "ppm" => Some(Self::Ppm),
+            _ => None,
+        }
+    }
+}
+
+/// Object safe view of a [`Solution`] so the registry can hold every day in
+/// one list.
+pub trait Runner {
+    fn day(&self) -> u8;
+    /// Fails if the input can't be parsed, otherwise returns the outcome of
+    /// each requested part.
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Vec<(Part, Result<String>)>>;
+    /// Times parsing, both parts and every [`Variant`], failing if any of them
+    /// errors or a variant disagrees with the main implementation.
+    fn bench(&self, input: &str, options: &BenchOptions) -> Result<Vec<(String, Stats)>>;
+    /// Parses the input and draws it with [`Solution::visualize`].
+    fn visualize(&self, input: &str, color: bool) -> Result<Option<String>>;
+    /// Parses the input and draws it with [`Solution::image`].
+    fn image(&self, input: &str, format: ImageFormat) -> Result<Option<Vec<u8>>>;
+}
+
+pub struct Day<S: Solution> {
+    day: u8,
+    solution: std::marker::PhantomData<S>,
+}
+
+impl<S: Solution> Day<S> {
+    pub const fn new(day: u8) -> Self {
+        Self {
+            day,
+            solution: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: Solution> Runner for Day<S> {
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Vec<(Part, Result<String>)>> {
+        let parsed = S::parse(input)?;
+        Ok(parts
+            .iter()
+            .map(|&part| match part {
+                Part::One => (part, S::part_1(&parsed).map(|a| a.to_string())),
+                Part::Two => (part, S::part_2(&parsed).map(|a| a.to_string())),
+            })
+            .collect())
+    }
+
+    fn bench(&self, input: &str, options: &BenchOptions) -> Result<Vec<(String, Stats)>> {
+        let parsed = S::parse(input)?;
+        let answer_1 = S::part_1(&parsed).context("part 1")?.to_string();
+        let answer_2 = S::part_2(&parsed).context("part 2")?.to_string();
+        let mut results = vec![
+            ("parse".to_string(), measure(options, || S::parse(input))),
+            (
+                "part 1".to_string(),
+                measure(options, || S::part_1(&parsed)),
+            ),
+            (
+                "part 2".to_string(),
+                measure(options, || S::part_2(&parsed)),
+            ),
+        ];
+        for variant in S::variants() {
+            let expected = match variant.part {
+                Part::One => &answer_1,
+                Part::Two => &answer_2,
+            };
+            let name = format!("part {} ({})", variant.part, variant.name);
+            let answer = (variant.run)(&parsed).context(name.clone())?;
+            if answer != *expected {
+                bail!("{} answered {} instead of {}", name, answer, expected);
+            }
+            results.push((name, measure(options, || (variant.run)(&parsed))));
+        }
+        Ok(results)
+    }
+
+    fn visualize(&self, input: &str, color: bool) -> Result<Option<String>> {
+        S::visualize(&S::parse(input)?, color)
+    }
+
+    fn image(&self, input: &str, format: ImageFormat) -> Result<Option<Vec<u8>>> {
+        S::image(&S::parse(input)?, format)
+    }
+}
@@ -1,39 +1,146 @@
-pub struct Chunk<I, const A: usize> {
+use std::{collections::VecDeque, fmt::Display};
+
+/// Fills an array from `iter`, handing back what it got if it ran out first.
+fn next_array<T, const A: usize>(iter: &mut impl Iterator<Item = T>) -> Result<[T; A], Vec<T>> {
+    let mut arr: [Option<T>; A] = std::array::from_fn(|_| None);
+    for (i, v) in arr.iter_mut().enumerate() {
+        match iter.next() {
+            Some(next) => *v = Some(next),
+            None => return Err(arr.into_iter().take(i).flatten().collect()),
+        }
+    }
+    Ok(arr.map(|v| v.unwrap()))
+}
+
+/// Groups of `A` items, a trailing partial group is kept in
+/// [`Chunk::remainder`].
+pub struct Chunk<I: Iterator, const A: usize> {
     iter: I,
+    remainder: Vec<I::Item>,
 }
 
-impl<I, T, const A: usize> Chunk<I, A>
-where
-    I: Iterator<Item = T>,
-    T: Default,
-{
+impl<I: Iterator, const A: usize> Chunk<I, A> {
     fn new(iter: I) -> Self {
-        Self { iter }
+        assert!(A > 0, "chunks must hold at least one item");
+        Self {
+            iter,
+            remainder: vec![],
+        }
+    }
+
+    /// Items left over once the iterator is exhausted, fewer than `A`.
+    pub fn remainder(&self) -> &[I::Item] {
+        &self.remainder
+    }
+}
+
+impl<I: Iterator, const A: usize> Iterator for Chunk<I, A> {
+    type Item = [I::Item; A];
+    fn next(&mut self) -> Option<Self::Item> {
+        match next_array(&mut self.iter) {
+            Ok(arr) => Some(arr),
+            Err(remainder) => {
+                if !remainder.is_empty() {
+                    self.remainder = remainder;
+                }
+                None
+            }
+        }
     }
 }
 
-impl<I, T, const A: usize> Iterator for Chunk<I, A>
+/// Error from [`ChunkExact`] when the items don't split evenly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeftoverError {
+    pub leftover: usize,
+    pub size: usize,
+}
+
+impl Display for LeftoverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} item(s) left over after the last group of {}",
+            self.leftover, self.size
+        )
+    }
+}
+
+impl std::error::Error for LeftoverError {}
+
+/// Like [`Chunk`] but a trailing partial group is yielded as an error.
+pub struct ChunkExact<I, const A: usize> {
+    iter: I,
+    done: bool,
+}
+
+impl<I: Iterator, const A: usize> Iterator for ChunkExact<I, A> {
+    type Item = Result<[I::Item; A], LeftoverError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match next_array(&mut self.iter) {
+            Ok(arr) => Some(Ok(arr)),
+            Err(remainder) => {
+                self.done = true;
+                (!remainder.is_empty()).then_some(Err(LeftoverError {
+                    leftover: remainder.len(),
+                    size: A,
+                }))
+            }
+        }
+    }
+}
+
+/// Every run of `A` consecutive items, like `slice::windows`.
+pub struct Windows<I: Iterator, const A: usize> {
+    iter: I,
+    window: VecDeque<I::Item>,
+}
+
+impl<I, const A: usize> Iterator for Windows<I, A>
 where
-    I: Iterator<Item = T>,
-    T: Default + Copy,
+    I: Iterator,
+    I::Item: Clone,
 {
-    type Item = [T; A];
+    type Item = [I::Item; A];
     fn next(&mut self) -> Option<Self::Item> {
-        let mut arr = [T::default(); A];
-        for v in arr.iter_mut() {
-            *v = self.iter.next()?;
+        if self.window.len() == A {
+            self.window.pop_front();
+        }
+        while self.window.len() < A {
+            self.window.push_back(self.iter.next()?);
         }
-        Some(arr)
+        Some(std::array::from_fn(|i| self.window[i].clone()))
     }
 }
 
-pub trait ChunkIterator<T: Default>: Iterator<Item = T> + Sized {
+/// Groups and windows of `A` items, panicking if `A` is 0 as there would be
+/// no end to the empty groups.
+pub trait ChunkIterator: Iterator + Sized {
     fn chunk<const A: usize>(self) -> Chunk<Self, A> {
         Chunk::new(self)
     }
+
+    fn chunk_exact<const A: usize>(self) -> ChunkExact<Self, A> {
+        assert!(A > 0, "chunks must hold at least one item");
+        ChunkExact {
+            iter: self,
+            done: false,
+        }
+    }
+
+    fn windows<const A: usize>(self) -> Windows<Self, A> {
+        assert!(A > 0, "windows must hold at least one item");
+        Windows {
+            iter: self,
+            window: VecDeque::with_capacity(A),
+        }
+    }
 }
 
-impl<T: Default, I: Iterator<Item = T>> ChunkIterator<T> for I {}
+impl<I: Iterator> ChunkIterator for I {}
 
 #[cfg(test)]
 mod test {
@@ -52,10 +159,10 @@ mod test {
         assert_eq!(answer.to_string(), a);
     }
 
-    #[derive(Default, Clone, Copy)]
+    #[derive(Default, Debug, PartialEq)]
     struct Foo {
         a: i32,
-        b: i32,
+        b: String,
     }
 
     #[test]
@@ -66,6 +173,48 @@ mod test {
             Foo::default(),
             Foo::default(),
         ];
-        // let a = lst.iter().chunk::<3>().collect::<Vec<_>>();
+        let mut chunks = lst.iter().chunk::<3>();
+        let a = chunks.by_ref().collect::<Vec<_>>();
+        assert_eq!(a, vec![[&lst[0], &lst[1], &lst[2]]]);
+        assert_eq!(chunks.remainder(), [&lst[3]]);
+    }
+
+    #[test]
+    fn test_chunk_exact() {
+        let even = (1..=4).chunk_exact::<2>().collect::<Result<Vec<_>, _>>();
+        assert_eq!(even, Ok(vec![[1, 2], [3, 4]]));
+        let odd = (1..=5).chunk_exact::<2>().collect::<Result<Vec<_>, _>>();
+        assert_eq!(
+            odd,
+            Err(LeftoverError {
+                leftover: 1,
+                size: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_windows() {
+        let windows = "abcd".chars().windows::<3>().collect::<Vec<_>>();
+        assert_eq!(windows, vec![['a', 'b', 'c'], ['b', 'c', 'd']]);
+        assert_eq!("ab".chars().windows::<3>().next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunks must hold at least one item")]
+    fn test_empty_chunk() {
+        (1..=4).chunk::<0>();
+    }
+
+    #[test]
+    #[should_panic(expected = "chunks must hold at least one item")]
+    fn test_empty_chunk_exact() {
+        (1..=4).chunk_exact::<0>();
+    }
+
+    #[test]
+    #[should_panic(expected = "windows must hold at least one item")]
+    fn test_empty_windows() {
+        (1..=4).windows::<0>();
     }
 }
@@ -0,0 +1,294 @@
+use super::{parse_error::ParseError, v2d::Vec2D};
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+/// `(row, column)` position in a [`Grid`].
+pub type Pos = (usize, usize);
+
+/// A cell that can be read from and drawn as a single character, used by the
+/// [`FromStr`] and [`Display`] impls of [`Grid`].
+pub trait Cell: Sized {
+    fn from_char(c: char) -> Option<Self>;
+    fn to_char(&self) -> char;
+}
+
+impl Cell for char {
+    fn from_char(c: char) -> Option<Self> {
+        Some(c)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+/// Fixed size 2D grid stored row by row in a single `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, `None` if they aren't all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Self {
+            cells: rows.into_iter().flatten().collect(),
+            width,
+            height,
+        })
+    }
+
+    /// Parses one cell per character with `f`, one row per line. Characters
+    /// `f` rejects and rows of the wrong length are reported as not being
+    /// `expected`.
+    pub fn parse_with(
+        s: &str,
+        expected: &str,
+        mut f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for line in s.lines().map(str::trim_end) {
+            let row_start = cells.len();
+            for (i, c) in line.char_indices() {
+                let cell =
+                    f(c).ok_or_else(|| ParseError::new(s, &line[i..i + c.len_utf8()], expected))?;
+                cells.push(cell);
+            }
+            let row_width = cells.len() - row_start;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(ParseError::new(
+                        s,
+                        line,
+                        format!("a row of {} cells", width),
+                    ))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                cells,
+                width,
+                height,
+            }),
+            _ => Err(ParseError::missing(s, "a grid")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if !self.contains(pos) {
+            return None;
+        }
+        self.cells.get(pos.0 * self.width + pos.1)
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if !self.contains(pos) {
+            return None;
+        }
+        self.cells.get_mut(pos.0 * self.width + pos.1)
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + 'static {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells[col..].iter().step_by(self.width)
+    }
+
+    /// Position one `direction` step from `pos`, if it is inside the grid.
+    pub fn step(&self, pos: Pos, direction: Vec2D) -> Option<Pos> {
+        (Vec2D::from_pos(pos) + direction).to_pos(self.width, self.height)
+    }
+
+    /// Up, down, left and right of `pos`, skipping those off the grid.
+    pub fn neighbours_4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Vec2D::DIRECTIONS_4
+            .into_iter()
+            .filter_map(move |direction| self.step(pos, direction))
+    }
+
+    /// All eight positions around `pos`, skipping those off the grid.
+    pub fn neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Vec2D::DIRECTIONS_8
+            .into_iter()
+            .filter_map(move |direction| self.step(pos, direction))
+    }
+
+    /// Cells from `pos` (not included) in a straight line in `direction` up
+    /// to the edge of the grid.
+    pub fn ray(&self, pos: Pos, direction: Vec2D) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            pos,
+            direction,
+        }
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside of a {}x{} grid",
+                pos, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside of a {}x{} grid", pos, width, height))
+    }
+}
+
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    pos: Pos,
+    direction: Vec2D,
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = (Pos, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pos = self.grid.step(self.pos, self.direction)?;
+        Some((self.pos, &self.grid[self.pos]))
+    }
+}
+
+impl<T: Cell> FromStr for Grid<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, "a grid cell", T::from_char)
+    }
+}
+
+impl<T: Cell> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid: Grid<char> = "abc\ndef\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.to_string(), "abc\ndef");
+
+        let err = "ab\nc".parse::<Grid<char>>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: expected a row of 2 cells"
+        );
+        let err = Grid::parse_with("12\n3x", "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 2: expected a digit");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(
+            grid.neighbours_4((0, 1)).collect::<Vec<_>>(),
+            vec![(1, 1), (0, 0), (0, 2)]
+        );
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours_8((2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = Grid::parse_with("123\n456\n789", "a digit", |c| c.to_digit(10)).unwrap();
+        let ray = |pos, direction| {
+            grid.ray(pos, direction)
+                .map(|(_, &v)| v)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ray((0, 0), Vec2D::DOWN_RIGHT), vec![5, 9]);
+        assert_eq!(ray((2, 1), Vec2D::UP), vec![5, 2]);
+        assert_eq!(ray((1, 2), Vec2D::RIGHT), vec![]);
+    }
+}
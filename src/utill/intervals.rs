@@ -0,0 +1,253 @@
+use std::{iter::Sum, ops::Range, ops::Sub};
+
+/// A set of values stored as sorted, disjoint half-open ranges. Ranges that
+/// overlap or touch are merged, so `0..2` and `2..4` are kept as `0..4`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: vec![] }
+    }
+}
+
+impl<T: Ord + Copy> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds every value of `range`, merging it with the ranges it overlaps or
+    /// touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let lo = self.ranges.partition_point(|r| r.end < range.start);
+        let hi = self.ranges.partition_point(|r| r.start <= range.end);
+        let mut merged = range;
+        if lo < hi {
+            merged.start = std::cmp::min(merged.start, self.ranges[lo].start);
+            merged.end = std::cmp::max(merged.end, self.ranges[hi - 1].end);
+        }
+        self.ranges.splice(lo..hi, [merged]);
+    }
+
+    /// Takes every value of `range` out, splitting a range it falls inside of.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let lo = self.ranges.partition_point(|r| r.end <= range.start);
+        let hi = self.ranges.partition_point(|r| r.start < range.end);
+        let mut kept = vec![];
+        if lo < hi {
+            if self.ranges[lo].start < range.start {
+                kept.push(self.ranges[lo].start..range.start);
+            }
+            if self.ranges[hi - 1].end > range.end {
+                kept.push(range.end..self.ranges[hi - 1].end);
+            }
+        }
+        self.ranges.splice(lo..hi, kept);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    /// Number of disjoint ranges, not of values.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The ranges in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &Range<T>> {
+        self.ranges.iter()
+    }
+
+    /// Ranges between consecutive ranges of the set, in ascending order.
+    pub fn gaps(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.ranges.windows(2).map(|w| w[0].end..w[1].start)
+    }
+
+    /// The values of `bounds` that aren't in the set.
+    pub fn complement(&self, bounds: Range<T>) -> Self {
+        Self {
+            ranges: self.complement_iter(bounds).collect(),
+        }
+    }
+
+    /// Ranges of [`Self::complement`] in ascending order, without collecting
+    /// them.
+    pub fn complement_iter(&self, bounds: Range<T>) -> impl Iterator<Item = Range<T>> + '_ {
+        let mut cur = bounds.start;
+        let mut ranges = self
+            .ranges
+            .iter()
+            .skip_while(move |r| r.end <= bounds.start);
+        std::iter::from_fn(move || {
+            while cur < bounds.end {
+                let Some(r) = ranges.next() else {
+                    let gap = cur..bounds.end;
+                    cur = bounds.end;
+                    return Some(gap);
+                };
+                let gap = cur..std::cmp::min(r.start, bounds.end);
+                cur = std::cmp::max(cur, r.end);
+                if !gap.is_empty() {
+                    return Some(gap);
+                }
+            }
+            None
+        })
+    }
+
+    /// Replaces the set with `ranges`, keeping its allocation so it can be
+    /// refilled in a loop.
+    pub fn refill(&mut self, ranges: impl IntoIterator<Item = Range<T>>) {
+        self.ranges.clear();
+        self.ranges
+            .extend(ranges.into_iter().filter(|r| !r.is_empty()));
+        self.ranges.sort_unstable_by_key(|r| r.start);
+        // merge in place, `len` ranges are kept at the front
+        let mut len = 0;
+        for i in 0..self.ranges.len() {
+            if len > 0 && self.ranges[i].start <= self.ranges[len - 1].end {
+                self.ranges[len - 1].end =
+                    std::cmp::max(self.ranges[len - 1].end, self.ranges[i].end);
+            } else {
+                self.ranges.swap(len, i);
+                len += 1;
+            }
+        }
+        self.ranges.truncate(len);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for r in other.ranges.iter() {
+            union.insert(r.clone());
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = std::cmp::max(a.start, b.start);
+            let end = std::cmp::min(a.end, b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            // move past whichever ends first
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+}
+
+impl<T: Ord + Copy + Sub<Output = T> + Sum> IntervalSet<T> {
+    /// Number of values in the set.
+    pub fn total_len(&self) -> T {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+}
+
+impl<T: Ord + Copy> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Ord + Copy> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.refill(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set = IntervalSet::new();
+        set.insert(5..8);
+        set.insert(0..2);
+        set.insert(2..3);
+        set.insert(10..10);
+        assert_eq!(set.iter().cloned().collect::<Vec<_>>(), vec![0..3, 5..8]);
+        set.insert(1..6);
+        assert_eq!(set.iter().cloned().collect::<Vec<_>>(), vec![0..8]);
+        set.remove(3..5);
+        set.remove(7..20);
+        assert_eq!(set.iter().cloned().collect::<Vec<_>>(), vec![0..3, 5..7]);
+        assert!(set.contains(0) && set.contains(6));
+        assert!(!set.contains(3) && !set.contains(7) && !set.contains(-1));
+        assert_eq!(set.total_len(), 5);
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_gaps_and_complement() {
+        let set = [4..6, 0..2, 8..9, 1..3]
+            .into_iter()
+            .collect::<IntervalSet<_>>();
+        assert_eq!(
+            set.iter().cloned().collect::<Vec<_>>(),
+            vec![0..3, 4..6, 8..9]
+        );
+        assert_eq!(set.gaps().collect::<Vec<_>>(), vec![3..4, 6..8]);
+        let outside = set.complement(-2..8);
+        assert_eq!(
+            outside.iter().cloned().collect::<Vec<_>>(),
+            vec![-2..0, 3..4, 6..8]
+        );
+        assert!(set.complement(0..3).is_empty());
+        assert_eq!(set.complement_iter(1..5).collect::<Vec<_>>(), vec![3..4]);
+        assert_eq!(
+            set.complement_iter(5..12).collect::<Vec<_>>(),
+            vec![6..8, 9..12]
+        );
+        assert_eq!(
+            set.complement_iter(20..30).collect::<Vec<_>>(),
+            vec![20..30]
+        );
+        assert_eq!(set.complement_iter(4..4).count(), 0);
+
+        let mut reused = set.clone();
+        reused.refill([7..9, 0..1, 8..12]);
+        assert_eq!(
+            reused.iter().cloned().collect::<Vec<_>>(),
+            vec![0..1, 7..12]
+        );
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let a = [0..4, 6..10].into_iter().collect::<IntervalSet<_>>();
+        let b = [2..7, 9..12].into_iter().collect::<IntervalSet<_>>();
+        assert_eq!(a.union(&b).iter().cloned().collect::<Vec<_>>(), vec![0..12]);
+        assert_eq!(
+            a.intersection(&b).iter().cloned().collect::<Vec<_>>(),
+            vec![2..4, 6..7, 9..10]
+        );
+        assert!(a.intersection(&IntervalSet::new()).is_empty());
+    }
+}
@@ -1,2 +1,6 @@
 pub mod chunk;
+pub mod grid;
+pub mod intervals;
+pub mod parse_error;
+pub mod search;
 pub mod v2d;
@@ -0,0 +1,172 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+/// Error produced by the day parsers, pointing at the exact line and columns
+/// of the input that didn't match what was expected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    columns: Range<usize>,
+    expected: String,
+    text: String,
+}
+
+/// Byte offset of `inner` inside `outer`, `inner` is expected to be a slice of
+/// `outer` but falls back to searching for it.
+fn offset_in(outer: &str, inner: &str) -> usize {
+    let start = outer.as_ptr() as usize;
+    let ptr = inner.as_ptr() as usize;
+    if ptr >= start && ptr + inner.len() <= start + outer.len() {
+        return ptr - start;
+    }
+    return outer.find(inner).unwrap_or(outer.len());
+}
+
+/// Line number (1 based), column and text of the line at `offset` in `src`.
+fn locate(src: &str, offset: usize) -> (usize, usize, &str) {
+    let line_start = src[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = src[offset..].find('\n').map_or(src.len(), |i| offset + i);
+    let line = src[..line_start].matches('\n').count() + 1;
+    return (line, offset - line_start, &src[line_start..line_end]);
+}
+
+impl ParseError {
+    /// Builds an error for `token`, which should be a slice of `src`. An empty
+    /// token marks a position, e.g. `&src[src.len()..]` for a missing value.
+    pub fn new(src: &str, token: &str, expected: impl Into<String>) -> Self {
+        let offset = offset_in(src, token);
+        let (line, column, text) = locate(src, offset);
+        let end = std::cmp::min(column + token.len(), text.len());
+        Self {
+            line,
+            columns: column..std::cmp::max(end, column + 1),
+            expected: expected.into(),
+            text: text.to_string(),
+        }
+    }
+
+    /// Error for something missing at the end of `src`.
+    pub fn missing(src: &str, expected: impl Into<String>) -> Self {
+        Self::new(src, &src[src.len()..], expected)
+    }
+
+    /// Parses `token` (a slice of `src`) or reports it as not being `expected`.
+    pub fn parse<T: FromStr>(src: &str, token: &str, expected: &str) -> Result<T, Self> {
+        token.parse().map_err(|_| Self::new(src, token, expected))
+    }
+
+    /// Moves an error produced while parsing `inner` so that it points into
+    /// `outer`, where `inner` is a slice of `outer`.
+    pub fn rebase(self, outer: &str, inner: &str) -> Self {
+        let (line, column, _) = locate(outer, offset_in(outer, inner));
+        let line = line + self.line - 1;
+        let shift = if self.line == 1 { column } else { 0 };
+        let text = outer.lines().nth(line - 1).unwrap_or(&self.text);
+        Self {
+            line,
+            columns: self.columns.start + shift..self.columns.end + shift,
+            text: text.to_string(),
+            ..self
+        }
+    }
+
+    /// Line of the input the error is on, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Columns of the offending text, starting at 0.
+    pub fn columns(&self) -> Range<usize> {
+        self.columns.clone()
+    }
+
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// Shows the offending line with the bad columns underlined.
+    ///
+    /// ```text
+    /// line 3, column 1: expected a file size
+    ///   |
+    /// 3 | 12x a.txt
+    ///   | ^^^
+    /// ```
+    pub fn render(&self) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        format!(
+            "{}\n{} |\n{} | {}\n{} | {}{}",
+            self,
+            gutter,
+            number,
+            self.text,
+            gutter,
+            " ".repeat(self.columns.start),
+            "^".repeat(self.columns.len())
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.columns.len() > 1 {
+            write!(
+                f,
+                "line {}, columns {}-{}: expected {}",
+                self.line,
+                self.columns.start + 1,
+                self.columns.end,
+                self.expected
+            )
+        } else {
+            write!(
+                f,
+                "line {}, column {}: expected {}",
+                self.line,
+                self.columns.start + 1,
+                self.expected
+            )
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let src = "1 2\n3 4x 5";
+        let err = ParseError::new(src, &src[6..8], "a number");
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.columns(), 2..4);
+        assert_eq!(err.to_string(), "line 2, columns 3-4: expected a number");
+        assert_eq!(
+            err.render(),
+            "line 2, columns 3-4: expected a number\n  |\n2 | 3 4x 5\n  |   ^^"
+        );
+    }
+
+    #[test]
+    fn test_missing() {
+        let err = ParseError::missing("move 1 from", "a stack");
+        assert_eq!(err.columns(), 11..12);
+        assert_eq!(err.to_string(), "line 1, column 12: expected a stack");
+    }
+
+    #[test]
+    fn test_rebase() {
+        let outer = "$ cd /\n$ ls\n12x a.txt";
+        let inner = &outer[9..];
+        let err = ParseError::parse::<u64>(inner, &inner[3..6], "a file size").unwrap_err();
+        let err = err.rebase(outer, inner);
+        assert_eq!(err.line(), 3);
+        assert_eq!(err.columns(), 0..3);
+        assert_eq!(
+            err.render(),
+            "line 3, columns 1-3: expected a file size\n  |\n3 | 12x a.txt\n  | ^^^"
+        );
+    }
+}
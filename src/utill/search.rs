@@ -0,0 +1,255 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Cost of a step or a path, anything that can be summed and compared with
+/// `Default` as zero.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T>> Cost for T {}
+
+/// How much work a search did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// States taken off the queue and expanded.
+    pub visited: usize,
+    /// States put on the queue, including ones later found cheaper.
+    pub queued: usize,
+}
+
+/// Everything a search reached, with the cheapest cost and the state it came
+/// from for each of them.
+#[derive(Clone, Debug)]
+pub struct Search<S, C> {
+    /// The first state the goal predicate accepted, if any.
+    pub goal: Option<S>,
+    pub stats: Stats,
+    reached: HashMap<S, (C, Option<S>)>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Search<S, C> {
+    fn new() -> Self {
+        Self {
+            goal: None,
+            stats: Stats::default(),
+            reached: HashMap::new(),
+        }
+    }
+
+    /// Cost of the cheapest path found to `state`. Exact for every state once
+    /// expanded, and for all of them if the search ran without a goal.
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.reached.get(state).map(|&(cost, _)| cost)
+    }
+
+    pub fn goal_cost(&self) -> Option<C> {
+        self.cost(self.goal.as_ref()?)
+    }
+
+    /// States from a start to `state`, both included.
+    pub fn path(&self, state: &S) -> Option<Vec<S>> {
+        let mut path = vec![state.clone()];
+        while let Some(parent) = self.reached.get(&path[path.len() - 1])?.1.clone() {
+            path.push(parent);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        self.path(self.goal.as_ref()?)
+    }
+
+    /// Every state reached with its cost.
+    pub fn costs(&self) -> impl Iterator<Item = (&S, C)> {
+        self.reached.iter().map(|(state, &(cost, _))| (state, cost))
+    }
+}
+
+/// Breadth first search where every step costs one, stopping at the first
+/// state `goal` accepts.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(entry) = search.reached.entry(start.clone()) {
+            entry.insert((0, None));
+            queue.push_back(start);
+            search.stats.queued += 1;
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        search.stats.visited += 1;
+        if goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        let cost = search.reached[&state].0 + 1;
+        for next in successors(&state) {
+            if let Entry::Vacant(entry) = search.reached.entry(next.clone()) {
+                entry.insert((cost, Some(state.clone())));
+                queue.push_back(next);
+                search.stats.queued += 1;
+            }
+        }
+    }
+    return search;
+}
+
+/// Cheapest paths for non negative step costs, stopping at the first state
+/// `goal` accepts.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    a_star(starts, successors, |_| C::default(), goal)
+}
+
+/// [`dijkstra`] guided by `heuristic`, which must never overestimate the cost
+/// left to a goal for the result to be the cheapest.
+pub fn a_star<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search::new();
+    let mut open = BinaryHeap::new();
+    for start in starts {
+        if let Entry::Vacant(entry) = search.reached.entry(start.clone()) {
+            entry.insert((C::default(), None));
+            open.push(Queued {
+                f: heuristic(&start),
+                g: C::default(),
+                state: start,
+            });
+            search.stats.queued += 1;
+        }
+    }
+    while let Some(Queued { g, state, .. }) = open.pop() {
+        // a cheaper way here was already expanded
+        if g > search.reached[&state].0 {
+            continue;
+        }
+        search.stats.visited += 1;
+        if goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        for (next, step) in successors(&state) {
+            let g = g + step;
+            if search
+                .reached
+                .get(&next)
+                .is_some_and(|&(best, _)| best <= g)
+            {
+                continue;
+            }
+            open.push(Queued {
+                f: g + heuristic(&next),
+                g,
+                state: next.clone(),
+            });
+            search.reached.insert(next, (g, Some(state.clone())));
+            search.stats.queued += 1;
+        }
+    }
+    return search;
+}
+
+/// Queue entry popping the lowest `f` first, the deepest on ties.
+struct Queued<S, C> {
+    f: C,
+    g: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Queued<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.f.cmp(&self.f).then_with(|| self.g.cmp(&other.g))
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Queued<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Queued<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S, C: Ord> Eq for Queued<S, C> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // a line 0..=10 with a shortcut 2 -> 8 costing 3
+    fn line(&n: &u32) -> Vec<(u32, u32)> {
+        let mut next = vec![];
+        if n < 10 {
+            next.push((n + 1, 1));
+        }
+        if n > 0 {
+            next.push((n - 1, 1));
+        }
+        if n == 2 {
+            next.push((8, 3));
+        }
+        next
+    }
+
+    #[test]
+    fn test_bfs() {
+        let search = bfs([0], |n| line(n).into_iter().map(|(n, _)| n), |&n| n == 9);
+        assert_eq!(search.goal, Some(9));
+        assert_eq!(search.goal_cost(), Some(4));
+        assert_eq!(search.goal_path(), Some(vec![0, 1, 2, 8, 9]));
+
+        let all = bfs([0], |n| line(n).into_iter().map(|(n, _)| n), |_| false);
+        assert_eq!(all.goal, None);
+        assert_eq!(all.costs().count(), 11);
+        assert_eq!(all.stats.visited, 11);
+    }
+
+    #[test]
+    fn test_dijkstra_and_a_star() {
+        let search = dijkstra([0], line, |&n| n == 9);
+        assert_eq!(search.goal_cost(), Some(6));
+        assert_eq!(search.goal_path(), Some(vec![0, 1, 2, 8, 9]));
+        assert_eq!(search.cost(&7), Some(6));
+
+        let guided = a_star([0], line, |&n| 9u32.abs_diff(n) / 3, |&n| n == 9);
+        assert_eq!(guided.goal_cost(), Some(6));
+        assert!(guided.stats.visited <= search.stats.visited);
+
+        let none = dijkstra([0], line, |&n| n == 11);
+        assert_eq!(none.goal_path(), None);
+    }
+}
@@ -1,5 +1,92 @@
-pub struct Vec2D(isize, isize);
-impl std::ops::Add for Vec2D {
+use super::{grid::Pos, parse_error::ParseError};
+use std::{
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// A 2D point or direction `(x, y)`, `y` growing downwards like the rows of a
+/// [`Grid`](super::grid::Grid).
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Vec2D(pub isize, pub isize);
+
+impl Vec2D {
+    pub const ZERO: Vec2D = Vec2D(0, 0);
+    pub const UP: Vec2D = Vec2D(0, -1);
+    pub const DOWN: Vec2D = Vec2D(0, 1);
+    pub const LEFT: Vec2D = Vec2D(-1, 0);
+    pub const RIGHT: Vec2D = Vec2D(1, 0);
+    pub const UP_LEFT: Vec2D = Vec2D(-1, -1);
+    pub const UP_RIGHT: Vec2D = Vec2D(1, -1);
+    pub const DOWN_LEFT: Vec2D = Vec2D(-1, 1);
+    pub const DOWN_RIGHT: Vec2D = Vec2D(1, 1);
+
+    /// Up, down, left and right.
+    pub const DIRECTIONS_4: [Vec2D; 4] = [Self::UP, Self::DOWN, Self::LEFT, Self::RIGHT];
+    /// Every direction including diagonals, clockwise from up.
+    pub const DIRECTIONS_8: [Vec2D; 8] = [
+        Self::UP,
+        Self::UP_RIGHT,
+        Self::RIGHT,
+        Self::DOWN_RIGHT,
+        Self::DOWN,
+        Self::DOWN_LEFT,
+        Self::LEFT,
+        Self::UP_LEFT,
+    ];
+
+    pub fn manhattan(self, rhs: Self) -> usize {
+        (self.0 - rhs.0).unsigned_abs() + (self.1 - rhs.1).unsigned_abs()
+    }
+
+    /// Distance when diagonal moves count as one step.
+    pub fn chebyshev(self, rhs: Self) -> usize {
+        std::cmp::max(
+            (self.0 - rhs.0).unsigned_abs(),
+            (self.1 - rhs.1).unsigned_abs(),
+        )
+    }
+
+    /// Each component clamped to -1, 0 or 1, the single step towards `self`.
+    pub fn signum(self) -> Self {
+        Self(self.0.signum(), self.1.signum())
+    }
+
+    /// Quarter turn clockwise, up becomes right.
+    pub fn rotate_right(self) -> Self {
+        Self(-self.1, self.0)
+    }
+
+    /// Quarter turn anticlockwise, up becomes left.
+    pub fn rotate_left(self) -> Self {
+        Self(self.1, -self.0)
+    }
+
+    /// `^`, `v`, `<` or `>` for a single step up, down, left or right.
+    pub fn to_arrow(self) -> Option<char> {
+        match self {
+            Self::UP => Some('^'),
+            Self::DOWN => Some('v'),
+            Self::LEFT => Some('<'),
+            Self::RIGHT => Some('>'),
+            _ => None,
+        }
+    }
+
+    /// The point at the `(row, column)` of a grid.
+    pub fn from_pos((row, col): Pos) -> Self {
+        Self(col as isize, row as isize)
+    }
+
+    /// The `(row, column)` of this point in a `width` by `height` grid, `None`
+    /// if it falls outside of it.
+    pub fn to_pos(self, width: usize, height: usize) -> Option<Pos> {
+        let col = usize::try_from(self.0).ok().filter(|&col| col < width)?;
+        let row = usize::try_from(self.1).ok().filter(|&row| row < height)?;
+        Some((row, col))
+    }
+}
+
+impl Add for Vec2D {
     type Output = Vec2D;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -7,10 +94,111 @@ impl std::ops::Add for Vec2D {
     }
 }
 
-impl std::ops::Sub for Vec2D {
+impl AddAssign for Vec2D {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Vec2D {
     type Output = Vec2D;
 
     fn sub(self, rhs: Self) -> Self::Output {
         Self(self.0 - rhs.0, self.1 - rhs.1)
     }
 }
+
+impl SubAssign for Vec2D {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Vec2D {
+    type Output = Vec2D;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0, -self.1)
+    }
+}
+
+impl Mul<isize> for Vec2D {
+    type Output = Vec2D;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Self(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+/// Parses a direction written as `U`/`D`/`L`/`R`, `N`/`S`/`E`/`W` or an
+/// arrow (`^v<>` or `↑↓←→`).
+impl FromStr for Vec2D {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "U" | "N" | "^" | "↑" => Ok(Self::UP),
+            "D" | "S" | "v" | "↓" => Ok(Self::DOWN),
+            "L" | "W" | "<" | "←" => Ok(Self::LEFT),
+            "R" | "E" | ">" | "→" => Ok(Self::RIGHT),
+            _ => Err(ParseError::new(
+                s,
+                s,
+                "a direction U/D/L/R, N/S/E/W or an arrow",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ops() {
+        let mut v = Vec2D(2, -3);
+        v += Vec2D::RIGHT * 2;
+        assert_eq!(v, Vec2D(4, -3));
+        assert_eq!(-v, Vec2D(-4, 3));
+        assert_eq!(v.signum(), Vec2D(1, -1));
+        assert_eq!(v.manhattan(Vec2D::ZERO), 7);
+        assert_eq!(v.chebyshev(Vec2D::ZERO), 4);
+    }
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(Vec2D::UP.rotate_right(), Vec2D::RIGHT);
+        assert_eq!(Vec2D::UP.rotate_left(), Vec2D::LEFT);
+        for d in Vec2D::DIRECTIONS_4 {
+            assert_eq!(d.rotate_right().rotate_left(), d);
+            assert_eq!(d.rotate_right().rotate_right(), -d);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let parsed = ["U", "N", "^", "↑", "E", ">", "→", "R"].map(|s| s.parse::<Vec2D>().unwrap());
+        assert_eq!(parsed[..4], [Vec2D::UP; 4]);
+        assert_eq!(parsed[4..], [Vec2D::RIGHT; 4]);
+        for d in Vec2D::DIRECTIONS_4 {
+            assert_eq!(
+                d.to_arrow().unwrap().to_string().parse::<Vec2D>().unwrap(),
+                d
+            );
+        }
+        assert_eq!(Vec2D::UP_LEFT.to_arrow(), None);
+        let err = "X".parse::<Vec2D>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 1: expected a direction U/D/L/R, N/S/E/W or an arrow"
+        );
+    }
+
+    #[test]
+    fn test_pos() {
+        assert_eq!(Vec2D::from_pos((2, 5)), Vec2D(5, 2));
+        assert_eq!(Vec2D(5, 2).to_pos(6, 3), Some((2, 5)));
+        assert_eq!(Vec2D(6, 2).to_pos(6, 3), None);
+        assert_eq!(Vec2D(-1, 0).to_pos(6, 3), None);
+    }
+}